@@ -45,7 +45,7 @@ fn run(args: Args) -> Result<()> {
     let mut dst_file = {
         let dst = &args.dst;
         let on_err = || format!("can not open destination file: path = {}", dst.display());
-        File::create(&dst).with_context(on_err)?
+        File::create(dst).with_context(on_err)?
     };
 
     let mut ring = {
@@ -95,9 +95,9 @@ mod chunk {
     #[derive(Debug, Clone, Copy)]
     #[repr(u8)]
     pub enum DataChunkState {
-        IDLE = 0,
-        READING = 1,
-        WRITING = 2,
+        Idle = 0,
+        Reading = 1,
+        Writing = 2,
     }
 
     pub struct DataChunkPtr(NonNull<DataChunkHeader>);
@@ -123,7 +123,7 @@ mod chunk {
                 header.chunk_size = chunk_size;
                 header.iov.iov_base = buf_ptr.cast();
                 header.iov.iov_len = 0;
-                header.state = DataChunkState::IDLE as u8;
+                header.state = DataChunkState::Idle as u8;
 
                 DataChunkPtr(NonNull::new_unchecked(header_ptr))
             }
@@ -293,7 +293,7 @@ fn copy_file(
             };
 
             chunk.set_full_filled();
-            *chunk.state_mut() = DataChunkState::READING;
+            *chunk.state_mut() = DataChunkState::Reading;
 
             // dbg!(("before readv", &chunk));
             unsafe {
@@ -315,7 +315,7 @@ fn copy_file(
             };
 
             let mut chunk = data_chunks.pop_front().unwrap();
-            *chunk.state_mut() = DataChunkState::WRITING;
+            *chunk.state_mut() = DataChunkState::Writing;
 
             // dbg!(("before writev", &chunk));
 
@@ -344,20 +344,20 @@ fn copy_file(
             // dbg!(("after IO completed", &chunk));
 
             match chunk.state_mut() {
-                DataChunkState::READING => {
+                DataChunkState::Reading => {
                     let n_read = cqe.io_result().context("IO operation failed: op = readv")?;
                     unsafe { chunk.set_data_range_unchecked(0..n_read as usize) };
-                    *chunk.state_mut() = DataChunkState::IDLE;
+                    *chunk.state_mut() = DataChunkState::Idle;
 
                     // dbg!(("after readv completed", &chunk));
                     data_chunks.push_back(chunk);
                 }
-                DataChunkState::WRITING => {
+                DataChunkState::Writing => {
                     let n_written = cqe
                         .io_result()
                         .context("IO operation failed: op = writev")?;
                     unsafe { chunk.consume_data_unchecked(n_written as usize) };
-                    *chunk.state_mut() = DataChunkState::IDLE;
+                    *chunk.state_mut() = DataChunkState::Idle;
 
                     // dbg!(("after writev completed", n_written, &chunk));
                     total_n_written += n_written as usize;
@@ -368,7 +368,7 @@ fn copy_file(
                         data_chunks.push_back(chunk)
                     }
                 }
-                DataChunkState::IDLE => unreachable!(),
+                DataChunkState::Idle => unreachable!(),
             }
         }
         let n_reaped = cqes.len() as u32;
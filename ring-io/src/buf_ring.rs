@@ -0,0 +1,237 @@
+use crate::cqe::CQE;
+use crate::register::Registrar;
+use crate::sys;
+use crate::utils::Mmap;
+
+use std::cell::Cell;
+use std::ops::{Deref, DerefMut};
+use std::ptr;
+use std::sync::atomic::{AtomicU16, Ordering};
+use std::{fmt, io, mem, slice};
+
+/// A registered ring of provided buffers.
+///
+/// The kernel selects a buffer from the ring when a request with
+/// [`SQE::set_buf_group`](crate::sqe::SQE::set_buf_group) is ready.
+/// [`CompletionQueue::take_buf`](crate::cq::CompletionQueue::take_buf) reaps the CQE
+/// and hands out the selected buffer, which goes back to the ring when the guard is dropped.
+///
+/// The ring is unregistered on drop.
+pub struct BufRing<'r> {
+    reg: Registrar<'r>,
+    ring: Mmap,
+    bufs: Mmap,
+    entries: u16,
+    buf_len: u32,
+    bgid: u16,
+    tail: Cell<u16>,
+    taken: Vec<Cell<bool>>,
+}
+
+unsafe impl Send for BufRing<'_> {}
+
+impl<'r> BufRing<'r> {
+    pub(crate) fn new(
+        reg: Registrar<'r>,
+        bgid: u16,
+        entries: u16,
+        buf_len: u32,
+    ) -> io::Result<Self> {
+        if !entries.is_power_of_two() || entries > 32768 {
+            return Err(io::Error::from_raw_os_error(libc::EINVAL));
+        }
+
+        let ring_size = usize::from(entries) * mem::size_of::<sys::io_uring_buf>();
+        let ring = Mmap::new(ring_size)?;
+
+        let bufs_size = (buf_len as usize)
+            .checked_mul(usize::from(entries))
+            .ok_or_else(|| io::Error::from_raw_os_error(libc::EINVAL))?;
+        let bufs = Mmap::new(bufs_size)?;
+
+        let mut buf_reg: sys::io_uring_buf_reg = unsafe { mem::zeroed() };
+        buf_reg.ring_addr = ring.ptr.as_ptr() as u64;
+        buf_reg.ring_entries = u32::from(entries);
+        buf_reg.bgid = bgid;
+
+        let opcode = sys::IORING_REGISTER_PBUF_RING;
+        let arg: *const sys::io_uring_buf_reg = &buf_reg;
+        unsafe { reg.register_raw(opcode, arg.cast(), 1)? };
+
+        let this = Self {
+            reg,
+            ring,
+            bufs,
+            entries,
+            buf_len,
+            bgid,
+            tail: Cell::new(0),
+            taken: (0..entries).map(|_| Cell::new(false)).collect(),
+        };
+
+        for bid in 0..entries {
+            this.push(bid);
+        }
+
+        Ok(this)
+    }
+
+    /// Returns the buffer group which should be passed to
+    /// [`SQE::set_buf_group`](crate::sqe::SQE::set_buf_group).
+    pub fn bgid(&self) -> u16 {
+        self.bgid
+    }
+
+    pub fn entries(&self) -> u16 {
+        self.entries
+    }
+
+    pub fn buf_len(&self) -> u32 {
+        self.buf_len
+    }
+
+    /// Takes the buffer selected for `cqe`, which must be reaped from the completion queue
+    /// once the guard is created, so that the same selection cannot be taken twice.
+    ///
+    /// Returns `None` if the CQE does not select a buffer or if the buffer has already been taken.
+    pub(crate) fn take(&self, cqe: &CQE) -> Option<BufRingGuard<'_, 'r>> {
+        let bid = cqe.buffer_id()?;
+        let taken = self.taken.get(usize::from(bid))?;
+        if taken.replace(true) {
+            return None;
+        }
+
+        let len = cqe.io_result().map_or(0, |n| n.min(self.buf_len));
+        Some(BufRingGuard {
+            ring: self,
+            bid,
+            len: len as usize,
+        })
+    }
+
+    fn buf_ptr(&self, bid: u16) -> *mut u8 {
+        let offset = usize::from(bid) * self.buf_len as usize;
+        unsafe { self.bufs.ptr.as_ptr().add(offset) }
+    }
+
+    /// Makes the buffer `bid` available to the kernel.
+    fn push(&self, bid: u16) {
+        let tail = self.tail.get();
+        let mask = self.entries - 1;
+        unsafe {
+            let base: *mut sys::io_uring_buf = self.ring.ptr.as_ptr().cast();
+            let entry = base.add(usize::from(tail & mask));
+            (*entry).addr = self.buf_ptr(bid) as u64;
+            (*entry).len = self.buf_len;
+            (*entry).bid = bid;
+
+            // the tail overlaps with `resv` of the first entry
+            let tail_ptr: *const AtomicU16 = ptr::addr_of!((*base).resv).cast();
+            (*tail_ptr).store(tail.wrapping_add(1), Ordering::Release);
+        }
+        self.tail.set(tail.wrapping_add(1));
+    }
+}
+
+impl Drop for BufRing<'_> {
+    fn drop(&mut self) {
+        let mut buf_reg: sys::io_uring_buf_reg = unsafe { mem::zeroed() };
+        buf_reg.bgid = self.bgid;
+
+        let opcode = sys::IORING_UNREGISTER_PBUF_RING;
+        let arg: *const sys::io_uring_buf_reg = &buf_reg;
+        let _ = unsafe { self.reg.register_raw(opcode, arg.cast(), 1) };
+    }
+}
+
+impl fmt::Debug for BufRing<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BufRing")
+            .field("bgid", &self.bgid)
+            .field("entries", &self.entries)
+            .field("buf_len", &self.buf_len)
+            .finish()
+    }
+}
+
+/// A buffer taken from a [`BufRing`], which goes back to the ring on drop.
+pub struct BufRingGuard<'a, 'r> {
+    ring: &'a BufRing<'r>,
+    bid: u16,
+    len: usize,
+}
+
+impl BufRingGuard<'_, '_> {
+    pub fn bid(&self) -> u16 {
+        self.bid
+    }
+}
+
+impl Deref for BufRingGuard<'_, '_> {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        unsafe { slice::from_raw_parts(self.ring.buf_ptr(self.bid), self.len) }
+    }
+}
+
+impl DerefMut for BufRingGuard<'_, '_> {
+    fn deref_mut(&mut self) -> &mut [u8] {
+        unsafe { slice::from_raw_parts_mut(self.ring.buf_ptr(self.bid), self.len) }
+    }
+}
+
+impl Drop for BufRingGuard<'_, '_> {
+    fn drop(&mut self) {
+        self.ring.taken[usize::from(self.bid)].set(false);
+        self.ring.push(self.bid);
+    }
+}
+
+impl fmt::Debug for BufRingGuard<'_, '_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BufRingGuard")
+            .field("bgid", &self.ring.bgid)
+            .field("bid", &self.bid)
+            .field("len", &self.len)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ring::RingBuilder;
+    use crate::sqe::{MsgFlags, PrepareSqe};
+
+    use std::io::Write;
+    use std::os::unix::io::AsRawFd;
+    use std::os::unix::net::UnixStream;
+    use std::ptr;
+
+    #[test]
+    fn take_buf_reaps_the_cqe() {
+        let mut ring = RingBuilder::new(4).build().unwrap();
+        let (mut sq, mut cq, reg) = ring.split();
+        let buf_ring = reg.register_buf_ring(1, 2, 64).unwrap();
+
+        let (mut tx, rx) = UnixStream::pair().unwrap();
+        tx.write_all(b"hello").unwrap();
+
+        unsafe {
+            let sqe = sq.get_sqe().unwrap();
+            sqe.prep_recv(rx.as_raw_fd(), ptr::null_mut(), 64, MsgFlags::empty());
+            sqe.set_buf_group(buf_ring.bgid());
+        }
+        sq.submit_and_wait(1).unwrap();
+
+        let (cqe, guard) = unsafe { cq.take_buf(&buf_ring) }.unwrap();
+        assert_eq!(&*guard, b"hello");
+        // a copy of the CQE does not hand out the buffer a second time
+        assert!(buf_ring.take(&cqe).is_none());
+        drop(guard);
+
+        // the CQE has been reaped, so the buffer cannot be taken again
+        assert_eq!(cq.ready(), 0);
+        assert!(unsafe { cq.take_buf(&buf_ring) }.is_none());
+    }
+}
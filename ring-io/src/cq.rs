@@ -1,3 +1,4 @@
+use crate::buf_ring::{BufRing, BufRingGuard};
 use crate::cqe::CQE;
 use crate::ring::{RawRing, RawRingPtr};
 use crate::{sys, utils};
@@ -62,6 +63,59 @@ impl CompletionQueue<'_> {
         unsafe { self.advance_unchecked(n) }
     }
 
+    /// Reaps CQEs until the terminal CQE of the multishot request `user_data`.
+    ///
+    /// The CQEs of the request, including the terminal one
+    /// which does not have [`CompletionFlags::MORE`](crate::cqe::CompletionFlags::MORE) set,
+    /// are passed to `on_item`. Other CQEs are passed to `on_other`.
+    ///
+    /// Waits for more CQEs when the queue is empty.
+    pub fn reap_multishot(
+        &mut self,
+        user_data: u64,
+        mut on_item: impl FnMut(&CQE),
+        mut on_other: impl FnMut(&CQE),
+    ) -> io::Result<()> {
+        loop {
+            if self.ready() == 0 {
+                self.wait_cqes(1)?;
+            }
+            let is_terminal = match self.peek_cqe() {
+                Some(cqe) if cqe.user_data() == user_data => {
+                    on_item(cqe);
+                    !cqe.has_more()
+                }
+                Some(cqe) => {
+                    on_other(cqe);
+                    false
+                }
+                None => continue,
+            };
+            self.advance(1);
+            if is_terminal {
+                return Ok(());
+            }
+        }
+    }
+
+    /// Reaps the next CQE and takes the buffer it selected from `buf_ring`.
+    ///
+    /// The guard holds the bytes received by the request.
+    /// Returns `None` without reaping if the queue is empty,
+    /// if the CQE does not select a buffer or if the buffer has already been taken.
+    ///
+    /// # Safety
+    /// The next CQE must come from a request which selected its buffer from the group of `buf_ring`.
+    pub unsafe fn take_buf<'a, 'r>(
+        &mut self,
+        buf_ring: &'a BufRing<'r>,
+    ) -> Option<(CQE, BufRingGuard<'a, 'r>)> {
+        let cqe = self.peek_cqe()?.clone();
+        let guard = buf_ring.take(&cqe)?;
+        self.advance(1);
+        Some((cqe, guard))
+    }
+
     pub fn ready(&self) -> u32 {
         unsafe {
             let ring_ptr = self.ring.get_mut_ptr();
@@ -104,3 +158,56 @@ impl fmt::Debug for CompletionQueue<'_> {
             .finish()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::ring::RingBuilder;
+    use crate::sqe::{PollEvents, PrepareSqe};
+
+    use std::io::Write;
+    use std::os::unix::io::AsRawFd;
+    use std::os::unix::net::UnixStream;
+
+    #[test]
+    fn reap_multishot_routes_cqes() {
+        let mut ring = RingBuilder::new(8).build().unwrap();
+        let (mut sq, mut cq, _reg) = ring.split();
+
+        let (mut tx, rx) = UnixStream::pair().unwrap();
+        tx.write_all(b"x").unwrap();
+
+        unsafe {
+            let sqe = sq.get_sqe().unwrap();
+            sqe.prep_poll_multishot(rx.as_raw_fd(), PollEvents::IN);
+            sqe.set_user_data(1);
+            sq.get_sqe().unwrap().prep_nop().set_user_data(2);
+        }
+        sq.submit_and_wait(2).unwrap();
+        unsafe { sq.get_sqe().unwrap().prep_poll_remove(1).set_user_data(3) };
+        sq.submit().unwrap();
+        cq.wait_cqes(4).unwrap();
+
+        // queued after the terminal CQE, so it is left for the caller
+        unsafe { sq.get_sqe().unwrap().prep_nop().set_user_data(4) };
+        sq.submit().unwrap();
+        cq.wait_cqes(5).unwrap();
+
+        let mut items = Vec::new();
+        let mut others = Vec::new();
+        cq.reap_multishot(
+            1,
+            |cqe| items.push((cqe.raw_result(), cqe.has_more())),
+            |cqe| others.push(cqe.user_data()),
+        )
+        .unwrap();
+
+        assert_eq!(items.len(), 2);
+        assert!(items[0].0 > 0 && items[0].1);
+        assert_eq!(items[1], (-libc::ECANCELED, false));
+        others.sort_unstable();
+        assert_eq!(others, [2, 3]);
+
+        assert_eq!(cq.ready(), 1);
+        assert_eq!(cq.peek_cqe().unwrap().user_data(), 4);
+    }
+}
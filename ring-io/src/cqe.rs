@@ -1,7 +1,10 @@
+use crate::sqe::CancelFlags;
 use crate::{sys, utils};
 
 use std::{fmt, io, ptr};
 
+use bitflags::bitflags;
+
 #[repr(transparent)]
 pub struct CQE {
     cqe: sys::io_uring_cqe,
@@ -21,6 +24,13 @@ impl CQE {
         self.cqe.flags
     }
 
+    /// Returns the flags of the CQE.
+    ///
+    /// Unknown flag bits are ignored.
+    pub fn flags(&self) -> CompletionFlags {
+        CompletionFlags::from_bits_truncate(self.cqe.flags)
+    }
+
     pub fn raw_result(&self) -> i32 {
         self.cqe.res
     }
@@ -34,6 +44,148 @@ impl CQE {
     pub fn is_err(&self) -> bool {
         self.cqe.res < 0
     }
+
+    /// Returns the id of the buffer selected by the kernel.
+    ///
+    /// See [`SQE::set_buf_group`](crate::sqe::SQE::set_buf_group).
+    pub fn buffer_id(&self) -> Option<u16> {
+        if self.flags().contains(CompletionFlags::BUFFER) {
+            Some((self.cqe.flags >> sys::IORING_CQE_BUFFER_SHIFT) as u16)
+        } else {
+            None
+        }
+    }
+
+    /// Interprets the result of a cancel request which has `flags`.
+    pub fn cancel_result(&self, flags: CancelFlags) -> io::Result<CancelResult> {
+        CancelResult::from_raw(self.cqe.res, flags)
+    }
+
+    /// Returns true if the request will post more CQEs.
+    pub fn has_more(&self) -> bool {
+        self.flags().contains(CompletionFlags::MORE)
+    }
+
+    /// Returns true if the CQE is the notification of a zero-copy send,
+    /// which means the kernel no longer uses the buffer.
+    pub fn is_notif(&self) -> bool {
+        self.flags().contains(CompletionFlags::NOTIF)
+    }
+}
+
+bitflags! {
+    pub struct CompletionFlags: u32 {
+        const BUFFER        = sys::IORING_CQE_F_BUFFER;
+        const MORE          = sys::IORING_CQE_F_MORE;
+        const SOCK_NONEMPTY = sys::IORING_CQE_F_SOCK_NONEMPTY;
+        const NOTIF         = sys::IORING_CQE_F_NOTIF;
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CancelResult {
+    /// The number of target requests which have been cancelled.
+    ///
+    /// It is always 1 without [`CancelFlags::ALL`] or [`CancelFlags::ANY`].
+    Cancelled(u32),
+    /// No target request was found.
+    NotFound,
+    /// The target request is already running and may not be cancelled.
+    AlreadyRunning,
+}
+
+impl CancelResult {
+    pub(crate) fn from_raw(res: i32, flags: CancelFlags) -> io::Result<Self> {
+        // the result is the number of cancelled requests if more than one can match
+        let counted = flags.intersects(CancelFlags::ALL | CancelFlags::ANY);
+        match res {
+            0 if counted => Ok(CancelResult::NotFound),
+            r if r > 0 && counted => Ok(CancelResult::Cancelled(r as u32)),
+            r if r >= 0 => Ok(CancelResult::Cancelled(1)),
+            r if r == -libc::ENOENT => Ok(CancelResult::NotFound),
+            r if r == -libc::EALREADY => Ok(CancelResult::AlreadyRunning),
+            r => Err(io::Error::from_raw_os_error(-r)),
+        }
+    }
+}
+
+/// Tracks a zero-copy send, which completes with a result CQE and a notification CQE.
+///
+/// It is returned by [`PrepareSqe::prep_send_zc_tracked`](crate::sqe::PrepareSqe::prep_send_zc_tracked)
+/// and borrows the buffer until both CQEs have been seen.
+/// If the result CQE does not have [`CompletionFlags::MORE`] set, no notification follows.
+#[must_use = "the buffer is in use until the tracker is done"]
+pub struct ZcTracker<'b> {
+    buf: &'b [u8],
+    user_data: u64,
+    result: Option<i32>,
+    notified: bool,
+}
+
+impl<'b> ZcTracker<'b> {
+    pub(crate) fn new(buf: &'b [u8], user_data: u64) -> Self {
+        Self {
+            buf,
+            user_data,
+            result: None,
+            notified: false,
+        }
+    }
+
+    pub fn buf(&self) -> &'b [u8] {
+        self.buf
+    }
+
+    pub fn user_data(&self) -> u64 {
+        self.user_data
+    }
+
+    /// Records `cqe` if it belongs to the tracked request.
+    ///
+    /// Returns false if the CQE has a different user data.
+    pub fn handle(&mut self, cqe: &CQE) -> bool {
+        if cqe.user_data() != self.user_data {
+            return false;
+        }
+        if cqe.is_notif() {
+            self.notified = true;
+        } else {
+            self.result = Some(cqe.raw_result());
+            if !cqe.has_more() {
+                self.notified = true;
+            }
+        }
+        true
+    }
+
+    /// Returns the result of the send if the result CQE has been seen.
+    pub fn result(&self) -> Option<io::Result<u32>> {
+        self.result.map(utils::resultify)
+    }
+
+    /// Returns true if both CQEs have been seen, so the buffer can be reused.
+    pub fn is_done(&self) -> bool {
+        self.result.is_some() && self.notified
+    }
+
+    /// Releases the buffer if both CQEs have been seen.
+    pub fn into_buf(self) -> Result<&'b [u8], Self> {
+        if self.is_done() {
+            Ok(self.buf)
+        } else {
+            Err(self)
+        }
+    }
+}
+
+impl fmt::Debug for ZcTracker<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ZcTracker")
+            .field("user_data", &self.user_data)
+            .field("result", &self.result)
+            .field("notified", &self.notified)
+            .finish()
+    }
 }
 
 impl Clone for CQE {
@@ -53,3 +205,66 @@ impl fmt::Debug for CQE {
             .finish()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cqe(res: i32, flags: u32) -> CQE {
+        CQE {
+            cqe: sys::io_uring_cqe {
+                user_data: 0,
+                res,
+                flags,
+            },
+        }
+    }
+
+    #[test]
+    fn buffer_id() {
+        let flags = (7 << sys::IORING_CQE_BUFFER_SHIFT) | sys::IORING_CQE_F_BUFFER;
+        assert_eq!(cqe(8, flags).buffer_id(), Some(7));
+
+        let flags = (0xffff << sys::IORING_CQE_BUFFER_SHIFT)
+            | sys::IORING_CQE_F_BUFFER
+            | sys::IORING_CQE_F_MORE;
+        let c = cqe(8, flags);
+        assert_eq!(c.buffer_id(), Some(0xffff));
+        assert!(c.has_more());
+
+        assert_eq!(cqe(8, 7 << sys::IORING_CQE_BUFFER_SHIFT).buffer_id(), None);
+        assert_eq!(cqe(-libc::ENOBUFS, 0).buffer_id(), None);
+    }
+
+    #[test]
+    fn cancel_result_counts() {
+        let single = CancelFlags::empty();
+        assert_eq!(
+            CancelResult::from_raw(0, single).unwrap(),
+            CancelResult::Cancelled(1)
+        );
+        let all = CancelFlags::ALL;
+        assert_eq!(
+            CancelResult::from_raw(0, all).unwrap(),
+            CancelResult::NotFound
+        );
+        assert_eq!(
+            CancelResult::from_raw(3, all).unwrap(),
+            CancelResult::Cancelled(3)
+        );
+        let any = CancelFlags::ANY;
+        assert_eq!(
+            CancelResult::from_raw(0, any).unwrap(),
+            CancelResult::NotFound
+        );
+        assert_eq!(
+            CancelResult::from_raw(-libc::ENOENT, single).unwrap(),
+            CancelResult::NotFound
+        );
+        assert_eq!(
+            CancelResult::from_raw(-libc::EALREADY, all).unwrap(),
+            CancelResult::AlreadyRunning
+        );
+        assert!(CancelResult::from_raw(-libc::EINVAL, single).is_err());
+    }
+}
@@ -0,0 +1,161 @@
+use crate::register::Registrar;
+
+use std::cell::RefCell;
+use std::os::unix::io::RawFd;
+use std::{fmt, io};
+
+/// An index into the registered file table.
+///
+/// It is handed out by [`FileTable::insert`] and [`FileTable::reserve`],
+/// but it is not tied to the table or its ring.
+/// A copy kept after [`FileTable::remove`] or [`FileTable::release`] refers to
+/// whichever file is put into the slot next,
+/// and it must only be used with the ring whose table handed it out.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct FixedFd(u32);
+
+impl FixedFd {
+    pub(crate) const fn new(index: u32) -> Self {
+        Self(index)
+    }
+
+    pub const fn index(self) -> u32 {
+        self.0
+    }
+}
+
+/// A sparse registered file table with a slot allocator.
+///
+/// [`FileTable::insert`] puts a file into a free slot and returns its [`FixedFd`].
+/// [`FileTable::remove`] clears the slot without touching the other slots,
+/// so in-flight requests on other files are not affected.
+///
+/// The table is unregistered on drop.
+pub struct FileTable<'r> {
+    reg: Registrar<'r>,
+    size: u32,
+    slots: RefCell<Slots>,
+}
+
+struct Slots {
+    used: Vec<bool>,
+    free: Vec<u32>,
+}
+
+impl Slots {
+    fn is_used(&self, index: u32) -> bool {
+        self.used.get(index as usize).copied().unwrap_or(false)
+    }
+}
+
+unsafe impl Send for FileTable<'_> {}
+
+impl<'r> FileTable<'r> {
+    pub(crate) fn new(reg: Registrar<'r>, size: u32) -> io::Result<Self> {
+        reg.register_files_sparse(size)?;
+        let slots = Slots {
+            used: vec![false; size as usize],
+            free: (0..size).rev().collect(),
+        };
+        Ok(Self {
+            reg,
+            size,
+            slots: RefCell::new(slots),
+        })
+    }
+
+    pub fn size(&self) -> u32 {
+        self.size
+    }
+
+    /// Returns the number of occupied slots.
+    pub fn len(&self) -> u32 {
+        self.size - self.slots.borrow().free.len() as u32
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Registers `fd` in a free slot.
+    ///
+    /// The kernel holds its own reference, so `fd` can be closed afterwards.
+    ///
+    /// Returns `ENFILE` if the table is full.
+    pub fn insert(&self, fd: RawFd) -> io::Result<FixedFd> {
+        let mut slots = self.slots.borrow_mut();
+        let index = slots
+            .free
+            .pop()
+            .ok_or_else(|| io::Error::from_raw_os_error(libc::ENFILE))?;
+        if let Err(err) = self.reg.update_files(index, &[fd]) {
+            slots.free.push(index);
+            return Err(err);
+        }
+        slots.used[index as usize] = true;
+        Ok(FixedFd::new(index))
+    }
+
+    /// Reserves a free slot for a direct descriptor,
+    /// such as [`PrepareSqe::prep_openat_direct`](crate::sqe::PrepareSqe::prep_openat_direct).
+    ///
+    /// If the request fails, the slot should be given back by [`FileTable::release`].
+    ///
+    /// Returns `ENFILE` if the table is full.
+    pub fn reserve(&self) -> io::Result<FixedFd> {
+        let mut slots = self.slots.borrow_mut();
+        let index = slots
+            .free
+            .pop()
+            .ok_or_else(|| io::Error::from_raw_os_error(libc::ENFILE))?;
+        slots.used[index as usize] = true;
+        Ok(FixedFd::new(index))
+    }
+
+    /// Makes the slot of `fixed` available again without touching the registered file.
+    ///
+    /// This is for a slot which is already empty, such as a reserved slot whose request failed,
+    /// or a slot closed by [`PrepareSqe::prep_close_direct`](crate::sqe::PrepareSqe::prep_close_direct).
+    ///
+    /// Returns `EBADF` if the slot is not occupied.
+    pub fn release(&self, fixed: FixedFd) -> io::Result<()> {
+        let mut slots = self.slots.borrow_mut();
+        let index = fixed.0;
+        if !slots.is_used(index) {
+            return Err(io::Error::from_raw_os_error(libc::EBADF));
+        }
+        slots.used[index as usize] = false;
+        slots.free.push(index);
+        Ok(())
+    }
+
+    /// Clears the slot of `fixed` and makes it available again.
+    ///
+    /// Returns `EBADF` if the slot is not occupied.
+    pub fn remove(&self, fixed: FixedFd) -> io::Result<()> {
+        let mut slots = self.slots.borrow_mut();
+        let index = fixed.0;
+        if !slots.is_used(index) {
+            return Err(io::Error::from_raw_os_error(libc::EBADF));
+        }
+        self.reg.update_files(index, &[-1])?;
+        slots.used[index as usize] = false;
+        slots.free.push(index);
+        Ok(())
+    }
+}
+
+impl Drop for FileTable<'_> {
+    fn drop(&mut self) {
+        let _ = self.reg.unregister_files();
+    }
+}
+
+impl fmt::Debug for FileTable<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FileTable")
+            .field("size", &self.size)
+            .field("len", &self.len())
+            .finish()
+    }
+}
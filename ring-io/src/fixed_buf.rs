@@ -0,0 +1,155 @@
+use crate::register::Registrar;
+use crate::utils::Mmap;
+
+use std::cell::RefCell;
+use std::ops::{Deref, DerefMut};
+use std::{fmt, io, slice};
+
+/// A pool of registered buffers.
+///
+/// The pool owns page-aligned memory and registers it with the ring.
+/// [`FixedBufPool::get`] hands out [`FixedBuf`] handles,
+/// which carry their buffer index and go back to the pool on drop.
+///
+/// The buffers are unregistered on drop, before the memory is freed.
+pub struct FixedBufPool<'r> {
+    reg: Registrar<'r>,
+    mem: Mmap,
+    n_bufs: u16,
+    buf_len: usize,
+    stride: usize,
+    free: RefCell<Vec<u16>>,
+}
+
+unsafe impl Send for FixedBufPool<'_> {}
+
+impl<'r> FixedBufPool<'r> {
+    pub(crate) fn new(reg: Registrar<'r>, n_bufs: u16, buf_len: usize) -> io::Result<Self> {
+        if n_bufs == 0 || buf_len == 0 {
+            return Err(io::Error::from_raw_os_error(libc::EINVAL));
+        }
+
+        let page_size = unsafe { libc::sysconf(libc::_SC_PAGESIZE) } as usize;
+        let stride = buf_len
+            .checked_add(page_size - 1)
+            .map(|n| n / page_size * page_size)
+            .ok_or_else(|| io::Error::from_raw_os_error(libc::EINVAL))?;
+        let mem_size = stride
+            .checked_mul(usize::from(n_bufs))
+            .ok_or_else(|| io::Error::from_raw_os_error(libc::EINVAL))?;
+        let mem = Mmap::new(mem_size)?;
+
+        let iovecs: Vec<libc::iovec> = (0..usize::from(n_bufs))
+            .map(|i| libc::iovec {
+                iov_base: unsafe { mem.ptr.as_ptr().add(i * stride).cast() },
+                iov_len: buf_len,
+            })
+            .collect();
+        unsafe { reg.register_buffers(iovecs.as_ptr(), iovecs.len())? };
+
+        Ok(Self {
+            reg,
+            mem,
+            n_bufs,
+            buf_len,
+            stride,
+            free: RefCell::new((0..n_bufs).rev().collect()),
+        })
+    }
+
+    pub fn n_bufs(&self) -> u16 {
+        self.n_bufs
+    }
+
+    pub fn buf_len(&self) -> usize {
+        self.buf_len
+    }
+
+    /// Returns the number of buffers which are not handed out.
+    pub fn available(&self) -> usize {
+        self.free.borrow().len()
+    }
+
+    /// Takes a free buffer from the pool.
+    ///
+    /// Returns `None` if all buffers are handed out.
+    pub fn get(&self) -> Option<FixedBuf<'_, 'r>> {
+        let index = self.free.borrow_mut().pop()?;
+        Some(FixedBuf { pool: self, index })
+    }
+
+    fn buf_ptr(&self, index: u16) -> *mut u8 {
+        let offset = usize::from(index) * self.stride;
+        unsafe { self.mem.ptr.as_ptr().add(offset) }
+    }
+}
+
+impl Drop for FixedBufPool<'_> {
+    fn drop(&mut self) {
+        let _ = self.reg.unregister_buffers();
+    }
+}
+
+impl fmt::Debug for FixedBufPool<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FixedBufPool")
+            .field("n_bufs", &self.n_bufs)
+            .field("buf_len", &self.buf_len)
+            .field("available", &self.available())
+            .finish()
+    }
+}
+
+/// A registered buffer taken from a [`FixedBufPool`], which goes back to the pool on drop.
+///
+/// Pass it to [`PrepareSqe::prep_read_fixed`](crate::sqe::PrepareSqe::prep_read_fixed)
+/// or [`PrepareSqe::prep_write_fixed`](crate::sqe::PrepareSqe::prep_write_fixed).
+pub struct FixedBuf<'a, 'r> {
+    pool: &'a FixedBufPool<'r>,
+    index: u16,
+}
+
+impl FixedBuf<'_, '_> {
+    /// Returns the index of the buffer in the registered buffer table.
+    pub fn index(&self) -> u16 {
+        self.index
+    }
+
+    pub fn as_ptr(&self) -> *const u8 {
+        self.pool.buf_ptr(self.index)
+    }
+
+    pub fn as_mut_ptr(&mut self) -> *mut u8 {
+        self.pool.buf_ptr(self.index)
+    }
+}
+
+impl Deref for FixedBuf<'_, '_> {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        unsafe { slice::from_raw_parts(self.as_ptr(), self.pool.buf_len) }
+    }
+}
+
+impl DerefMut for FixedBuf<'_, '_> {
+    fn deref_mut(&mut self) -> &mut [u8] {
+        let len = self.pool.buf_len;
+        unsafe { slice::from_raw_parts_mut(self.as_mut_ptr(), len) }
+    }
+}
+
+impl Drop for FixedBuf<'_, '_> {
+    fn drop(&mut self) {
+        self.pool.free.borrow_mut().push(self.index);
+    }
+}
+
+impl fmt::Debug for FixedBuf<'_, '_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FixedBuf")
+            .field("index", &self.index)
+            .field("len", &self.pool.buf_len)
+            .finish()
+    }
+}
@@ -8,9 +8,14 @@ mod sys;
 #[macro_use]
 mod utils;
 
+pub mod buf_ring;
 pub mod cq;
 pub mod cqe;
+pub mod file_table;
+pub mod fixed_buf;
+pub mod probe;
 pub mod register;
 pub mod ring;
+pub mod splice;
 pub mod sq;
 pub mod sqe;
@@ -0,0 +1,58 @@
+use crate::sqe::Opcode;
+use crate::{sys, utils};
+
+use std::ptr::NonNull;
+use std::{fmt, io, mem};
+
+/// The opcodes supported by the running kernel.
+pub struct Probe {
+    probe: NonNull<sys::io_uring_probe>,
+}
+
+unsafe impl Send for Probe {}
+unsafe impl Sync for Probe {}
+
+impl Probe {
+    const MAX_OPS: usize = 256;
+
+    pub(crate) unsafe fn new_with(ring_ptr: *mut sys::io_uring) -> io::Result<Self> {
+        let size = mem::size_of::<sys::io_uring_probe>()
+            + Self::MAX_OPS * mem::size_of::<sys::io_uring_probe_op>();
+
+        let probe = match NonNull::new(libc::calloc(1, size)) {
+            Some(p) => Self { probe: p.cast() },
+            None => return Err(io::Error::from_raw_os_error(libc::ENOMEM)),
+        };
+
+        let probe_ptr = probe.probe.as_ptr();
+        let ret = sys::io_uring_register_probe(ring_ptr, probe_ptr, Self::MAX_OPS as u32);
+        utils::resultify(ret)?;
+
+        Ok(probe)
+    }
+
+    pub fn is_supported(&self, op: Opcode) -> bool {
+        unsafe {
+            let probe_ptr = self.probe.as_ptr();
+            sys::io_uring_opcode_supported(probe_ptr, op as libc::c_int) != 0
+        }
+    }
+}
+
+impl Drop for Probe {
+    fn drop(&mut self) {
+        unsafe { libc::free(self.probe.as_ptr().cast()) }
+    }
+}
+
+impl fmt::Debug for Probe {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let supported: Vec<Opcode> = (0..=u8::MAX)
+            .filter_map(Opcode::from_u8)
+            .filter(|&op| self.is_supported(op))
+            .collect();
+        f.debug_struct("Probe")
+            .field("supported", &supported)
+            .finish()
+    }
+}
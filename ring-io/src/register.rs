@@ -1,8 +1,14 @@
+use crate::buf_ring::BufRing;
+use crate::cqe::CancelResult;
+use crate::file_table::FileTable;
+use crate::fixed_buf::FixedBufPool;
+use crate::probe::Probe;
 use crate::ring::{RawRing, RawRingPtr};
+use crate::sqe::{CancelFlags, Target, Timespec};
 use crate::{sys, utils};
 
 use std::os::unix::io::RawFd;
-use std::{fmt, io};
+use std::{fmt, io, mem};
 
 pub struct Registrar<'r> {
     ring: RawRingPtr<'r>,
@@ -11,13 +17,27 @@ pub struct Registrar<'r> {
 unsafe impl Send for Registrar<'_> {}
 unsafe impl Sync for Registrar<'_> {}
 
-impl Registrar<'_> {
+impl<'r> Registrar<'r> {
     pub(crate) unsafe fn new_unchecked(ptr: *mut RawRing) -> Self {
         Self {
             ring: RawRingPtr::new_unchecked(ptr),
         }
     }
 
+    pub(crate) unsafe fn register_raw(
+        &self,
+        opcode: u32,
+        arg: *const libc::c_void,
+        nr_args: u32,
+    ) -> io::Result<u32> {
+        let fd = self.ring.get_ref().ring_fd();
+        let ret = sys::syscalls::io_uring_register(fd, opcode, arg, nr_args);
+        if ret < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(ret as u32)
+    }
+
     /// # Safety
     pub unsafe fn register_buffers(
         &self,
@@ -46,12 +66,132 @@ impl Registrar<'_> {
         Ok(())
     }
 
+    /// Registers a sparse table of `nr_files` empty slots.
+    ///
+    /// The slots can be filled by [`Registrar::update_files`].
+    pub fn register_files_sparse(&self, nr_files: u32) -> io::Result<()> {
+        let files: Vec<RawFd> = vec![-1; nr_files as usize];
+        self.register_files(&files)
+    }
+
+    /// Replaces the registered files starting at slot `offset`.
+    ///
+    /// A `-1` clears the slot.
+    /// Returns the number of updated slots.
+    pub fn update_files(&self, offset: u32, files: &[RawFd]) -> io::Result<u32> {
+        let ring_ptr = self.ring.get_mut_ptr();
+        let files_ptr = files.as_ptr();
+        let nr_files = files.len() as u32;
+        let ret =
+            unsafe { sys::io_uring_register_files_update(ring_ptr, offset, files_ptr, nr_files) };
+        utils::resultify(ret)
+    }
+
     pub fn unregister_files(&self) -> io::Result<()> {
         let ring_ptr = self.ring.get_mut_ptr();
         let ret = unsafe { sys::io_uring_unregister_files(ring_ptr) };
         utils::resultify(ret)?;
         Ok(())
     }
+
+    /// Cancels the request which has `target_user_data` and waits for it to complete.
+    ///
+    /// Returns [`io::ErrorKind::TimedOut`] if `timeout` expires first.
+    pub fn sync_cancel(
+        &self,
+        target_user_data: u64,
+        flags: CancelFlags,
+        timeout: Option<Timespec>,
+    ) -> io::Result<CancelResult> {
+        self.sync_cancel_with(target_user_data, -1, flags, 0, timeout)
+    }
+
+    /// Cancels the request which operates on `fd` and waits for it to complete.
+    ///
+    /// Returns [`io::ErrorKind::TimedOut`] if `timeout` expires first.
+    pub fn sync_cancel_fd(
+        &self,
+        fd: impl Into<Target>,
+        flags: CancelFlags,
+        timeout: Option<Timespec>,
+    ) -> io::Result<CancelResult> {
+        let fd = fd.into();
+        let mut fd_flags = sys::IORING_ASYNC_CANCEL_FD;
+        if fd.is_fixed() {
+            fd_flags |= sys::IORING_ASYNC_CANCEL_FD_FIXED;
+        }
+        self.sync_cancel_with(0, fd.raw(), flags, fd_flags, timeout)
+    }
+
+    fn sync_cancel_with(
+        &self,
+        addr: u64,
+        fd: RawFd,
+        flags: CancelFlags,
+        fd_flags: u32,
+        timeout: Option<Timespec>,
+    ) -> io::Result<CancelResult> {
+        let mut reg: sys::io_uring_sync_cancel_reg = unsafe { mem::zeroed() };
+        reg.addr = addr;
+        reg.fd = fd;
+        reg.flags = flags.bits() | fd_flags;
+        match timeout {
+            Some(ts) => {
+                reg.timeout.tv_sec = ts.sec();
+                reg.timeout.tv_nsec = i64::from(ts.nsec());
+            }
+            None => {
+                reg.timeout.tv_sec = -1;
+                reg.timeout.tv_nsec = -1;
+            }
+        }
+
+        let opcode = sys::IORING_REGISTER_SYNC_CANCEL;
+        let arg: *const sys::io_uring_sync_cancel_reg = &reg;
+        match unsafe { self.register_raw(opcode, arg.cast(), 1) } {
+            Ok(n) => CancelResult::from_raw(n as i32, flags),
+            Err(err) => match err.raw_os_error() {
+                Some(libc::ETIME) => Err(io::Error::new(io::ErrorKind::TimedOut, err)),
+                Some(errno) => CancelResult::from_raw(-errno, flags),
+                None => Err(err),
+            },
+        }
+    }
+
+    /// Allocates and registers a ring of `entries` buffers of `buf_len` bytes as the group `bgid`.
+    ///
+    /// `entries` must be a power of two and not greater than 32768.
+    pub fn register_buf_ring(
+        &self,
+        bgid: u16,
+        entries: u16,
+        buf_len: u32,
+    ) -> io::Result<BufRing<'r>> {
+        let reg = unsafe { Registrar::new_unchecked(self.ring.as_raw_ptr()) };
+        BufRing::new(reg, bgid, entries, buf_len)
+    }
+
+    /// Allocates `n_bufs` page-aligned buffers of `buf_len` bytes and registers them.
+    ///
+    /// Only one set of buffers can be registered with a ring at a time.
+    pub fn register_buf_pool(&self, n_bufs: u16, buf_len: usize) -> io::Result<FixedBufPool<'r>> {
+        let reg = unsafe { Registrar::new_unchecked(self.ring.as_raw_ptr()) };
+        FixedBufPool::new(reg, n_bufs, buf_len)
+    }
+
+    /// Registers a sparse table of `size` slots with a slot allocator.
+    ///
+    /// Only one file table can be registered with a ring at a time.
+    pub fn register_file_table(&self, size: u32) -> io::Result<FileTable<'r>> {
+        let reg = unsafe { Registrar::new_unchecked(self.ring.as_raw_ptr()) };
+        FileTable::new(reg, size)
+    }
+
+    /// Queries which opcodes are supported by the running kernel.
+    pub fn probe(&self) -> io::Result<Probe> {
+        let ring_ptr = self.ring.get_mut_ptr();
+        unsafe { Probe::new_with(ring_ptr) }
+    }
 }
 
 impl fmt::Debug for Registrar<'_> {
@@ -1,15 +1,20 @@
 use crate::cq::CompletionQueue;
+use crate::file_table::FixedFd;
 use crate::register::Registrar;
 use crate::sq::SubmissionQueue;
+use crate::sqe::{PrepareSqe, SQE};
 use crate::{sys, utils};
 
 use std::cell::UnsafeCell;
 use std::marker::PhantomData;
 use std::mem::MaybeUninit;
-use std::os::unix::io::RawFd;
+use std::os::unix::io::{AsRawFd, RawFd};
 use std::ptr::NonNull;
+use std::time::Duration;
 use std::{fmt, io, mem};
 
+use bitflags::bitflags;
+
 #[repr(transparent)]
 pub(crate) struct RawRing(UnsafeCell<sys::io_uring>);
 
@@ -45,6 +50,10 @@ impl RawRingPtr<'_> {
         unsafe { self.0.as_ref() }
     }
 
+    pub fn as_raw_ptr(&self) -> *mut RawRing {
+        self.0.as_ptr()
+    }
+
     pub fn get_mut_ptr(&self) -> *mut sys::io_uring {
         self.0.as_ptr().cast()
     }
@@ -52,6 +61,7 @@ impl RawRingPtr<'_> {
 
 pub struct Ring {
     ring: RawRing,
+    params: sys::io_uring_params,
 }
 
 unsafe impl Send for Ring {}
@@ -73,10 +83,76 @@ impl RingBuilder {
         }
     }
 
+    pub fn flags(mut self, flags: SetupFlags) -> Self {
+        self.params.flags |= flags.bits();
+        self
+    }
+
+    /// Creates a kernel thread to poll the submission queue.
+    pub fn sqpoll(self) -> Self {
+        self.flags(SetupFlags::SQPOLL)
+    }
+
+    /// Sets how long the SQ polling thread may stay idle before it goes to sleep.
+    ///
+    /// The duration is truncated to milliseconds.
+    pub fn sq_thread_idle(mut self, idle: Duration) -> Self {
+        let millis = idle.as_millis().min(u128::from(u32::MAX));
+        self.params.sq_thread_idle = millis as u32; // safe cast: millis <= u32::MAX
+        self
+    }
+
+    /// Binds the SQ polling thread to `cpu`.
+    pub fn sq_thread_cpu(mut self, cpu: u32) -> Self {
+        self.params.sq_thread_cpu = cpu;
+        self.flags(SetupFlags::SQ_AFF)
+    }
+
+    /// Performs busy-waiting for IO completions.
+    pub fn iopoll(self) -> Self {
+        self.flags(SetupFlags::IOPOLL)
+    }
+
+    /// Creates the completion queue with `entries` entries instead of twice the SQ size.
+    pub fn cq_size(mut self, entries: u32) -> Self {
+        self.params.cq_entries = entries;
+        self.flags(SetupFlags::CQSIZE)
+    }
+
+    /// Clamps the SQ and CQ sizes to their maximum values instead of failing.
+    pub fn clamp(self) -> Self {
+        self.flags(SetupFlags::CLAMP)
+    }
+
+    /// Continues submitting SQEs even if one of them fails.
+    pub fn submit_all(self) -> Self {
+        self.flags(SetupFlags::SUBMIT_ALL)
+    }
+
+    /// Runs task work cooperatively instead of interrupting the submitter.
+    pub fn coop_taskrun(self) -> Self {
+        self.flags(SetupFlags::COOP_TASKRUN)
+    }
+
+    /// Promises that only one task will submit requests.
+    pub fn single_issuer(self) -> Self {
+        self.flags(SetupFlags::SINGLE_ISSUER)
+    }
+
+    /// Defers task work until the submitter waits for completions.
+    ///
+    /// Requires [`SetupFlags::SINGLE_ISSUER`].
+    pub fn defer_taskrun(self) -> Self {
+        self.flags(SetupFlags::DEFER_TASKRUN)
+    }
+
     pub fn build(mut self) -> io::Result<Ring> {
         unsafe {
             let ring = RawRing::new(self.entries, &mut self.params)?;
-            Ok(Ring { ring })
+            Ok(Ring {
+                ring,
+                params: self.params,
+            })
         }
     }
 }
@@ -84,14 +160,86 @@ impl RingBuilder {
 impl fmt::Debug for RingBuilder {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("RingBuilder")
-            .field("entries", &self.entries) // TODO: more info
+            .field("entries", &self.entries)
+            .field("flags", &SetupFlags::from_bits_truncate(self.params.flags))
+            .field("cq_entries", &self.params.cq_entries)
+            .field("sq_thread_cpu", &self.params.sq_thread_cpu)
+            .field("sq_thread_idle", &self.params.sq_thread_idle)
             .finish()
     }
 }
 
+bitflags! {
+    pub struct SetupFlags: u32 {
+        const IOPOLL            = sys::IORING_SETUP_IOPOLL;
+        const SQPOLL            = sys::IORING_SETUP_SQPOLL;
+        const SQ_AFF            = sys::IORING_SETUP_SQ_AFF;
+        const CQSIZE            = sys::IORING_SETUP_CQSIZE;
+        const CLAMP             = sys::IORING_SETUP_CLAMP;
+        const SUBMIT_ALL        = sys::IORING_SETUP_SUBMIT_ALL;
+        const COOP_TASKRUN      = sys::IORING_SETUP_COOP_TASKRUN;
+        const TASKRUN_FLAG      = sys::IORING_SETUP_TASKRUN_FLAG;
+        const SINGLE_ISSUER     = sys::IORING_SETUP_SINGLE_ISSUER;
+        const DEFER_TASKRUN     = sys::IORING_SETUP_DEFER_TASKRUN;
+    }
+}
+
+bitflags! {
+    pub struct RingFeatures: u32 {
+        const SINGLE_MMAP       = sys::IORING_FEAT_SINGLE_MMAP;
+        const NODROP            = sys::IORING_FEAT_NODROP;
+        const SUBMIT_STABLE     = sys::IORING_FEAT_SUBMIT_STABLE;
+        const RW_CUR_POS        = sys::IORING_FEAT_RW_CUR_POS;
+        const CUR_PERSONALITY   = sys::IORING_FEAT_CUR_PERSONALITY;
+        const FAST_POLL         = sys::IORING_FEAT_FAST_POLL;
+        const POLL_32BITS       = sys::IORING_FEAT_POLL_32BITS;
+        const SQPOLL_NONFIXED   = sys::IORING_FEAT_SQPOLL_NONFIXED;
+        const EXT_ARG           = sys::IORING_FEAT_EXT_ARG;
+        const NATIVE_WORKERS    = sys::IORING_FEAT_NATIVE_WORKERS;
+        const RSRC_TAGS         = sys::IORING_FEAT_RSRC_TAGS;
+        const CQE_SKIP          = sys::IORING_FEAT_CQE_SKIP;
+        const LINKED_FILE       = sys::IORING_FEAT_LINKED_FILE;
+        const REG_REG_RING      = sys::IORING_FEAT_REG_REG_RING;
+        const RECVSEND_BUNDLE   = sys::IORING_FEAT_RECVSEND_BUNDLE;
+        const MIN_TIMEOUT       = sys::IORING_FEAT_MIN_TIMEOUT;
+    }
+}
+
 impl Ring {
+    // --- getters ---
+
+    /// Returns the features supported by the kernel.
+    ///
+    /// Unknown feature bits are ignored.
+    pub fn features(&self) -> RingFeatures {
+        RingFeatures::from_bits_truncate(self.params.features)
+    }
+
+    pub fn setup_flags(&self) -> SetupFlags {
+        SetupFlags::from_bits_truncate(self.params.flags)
+    }
+
+    /// Returns the number of SQ entries chosen by the kernel.
+    pub fn sq_entries(&self) -> u32 {
+        self.params.sq_entries
+    }
+
+    /// Returns the number of CQ entries chosen by the kernel.
+    pub fn cq_entries(&self) -> u32 {
+        self.params.cq_entries
+    }
+
+    pub fn ring_fd(&self) -> RawFd {
+        self.ring.ring_fd()
+    }
+
     // --- methods ---
 
+    /// Creates a handle which can be sent to other threads as a message target.
+    pub fn handle(&self) -> io::Result<RingHandle> {
+        RingHandle::new(self.ring_fd())
+    }
+
     pub fn sq(&mut self) -> SubmissionQueue<'_> {
         unsafe { SubmissionQueue::new_unchecked(&mut self.ring) }
     }
@@ -123,9 +271,83 @@ impl Drop for Ring {
     }
 }
 
+/// An owned duplicate of a ring fd, which is the target of
+/// [`PrepareSqe::prep_msg_ring`] and [`PrepareSqe::prep_msg_ring_fd`].
+///
+/// The ring stays alive until the handle is dropped, even if the [`Ring`] has been dropped.
+pub struct RingHandle {
+    fd: RawFd,
+}
+
+impl RingHandle {
+    fn new(ring_fd: RawFd) -> io::Result<Self> {
+        let fd = unsafe { libc::fcntl(ring_fd, libc::F_DUPFD_CLOEXEC, 0) };
+        if fd < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(Self { fd })
+    }
+
+    pub fn try_clone(&self) -> io::Result<Self> {
+        Self::new(self.fd)
+    }
+
+    /// Prepares `sqe` to post a CQE with `result` and `user_data` to this ring.
+    ///
+    /// # Safety
+    /// See [`SQE`]
+    pub unsafe fn prep_msg<'s>(
+        &self,
+        sqe: &'s mut (impl PrepareSqe + ?Sized),
+        result: i32,
+        user_data: u64,
+    ) -> &'s mut SQE {
+        sqe.prep_msg_ring(self, result, user_data)
+    }
+
+    /// Prepares `sqe` to install the registered file `src` into the slot `dst` of this ring.
+    ///
+    /// See [`PrepareSqe::prep_msg_ring_fd`].
+    ///
+    /// # Safety
+    /// See [`SQE`]
+    pub unsafe fn prep_send_fd<'s>(
+        &self,
+        sqe: &'s mut (impl PrepareSqe + ?Sized),
+        src: FixedFd,
+        dst: FixedFd,
+        user_data: u64,
+    ) -> &'s mut SQE {
+        sqe.prep_msg_ring_fd(self, src, dst, user_data)
+    }
+}
+
+impl AsRawFd for RingHandle {
+    fn as_raw_fd(&self) -> RawFd {
+        self.fd
+    }
+}
+
+impl Drop for RingHandle {
+    fn drop(&mut self) {
+        unsafe { libc::close(self.fd) };
+    }
+}
+
+impl fmt::Debug for RingHandle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RingHandle").field("fd", &self.fd).finish()
+    }
+}
+
 impl fmt::Debug for Ring {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let fd = self.ring.ring_fd();
-        f.debug_struct("Ring").field("ring_fd", &fd).finish() // TODO: more info
+        f.debug_struct("Ring")
+            .field("ring_fd", &self.ring_fd())
+            .field("sq_entries", &self.sq_entries())
+            .field("cq_entries", &self.cq_entries())
+            .field("setup_flags", &self.setup_flags())
+            .field("features", &self.features())
+            .finish()
     }
 }
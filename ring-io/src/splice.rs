@@ -0,0 +1,80 @@
+use std::os::unix::io::RawFd;
+use std::{fmt, io};
+
+/// An owned pipe which relays data between two fds by splicing.
+///
+/// See [`SubmissionQueue::prep_sendfile`](crate::sq::SubmissionQueue::prep_sendfile).
+pub struct SplicePipe {
+    fds: [RawFd; 2],
+    capacity: u32,
+}
+
+impl SplicePipe {
+    pub fn new() -> io::Result<Self> {
+        let mut fds: [RawFd; 2] = [-1; 2];
+        let ret = unsafe { libc::pipe2(fds.as_mut_ptr(), libc::O_CLOEXEC) };
+        if ret < 0 {
+            return Err(io::Error::last_os_error());
+        }
+
+        let mut pipe = Self { fds, capacity: 0 };
+
+        let ret = unsafe { libc::fcntl(pipe.write_fd(), libc::F_GETPIPE_SZ) };
+        if ret < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        pipe.capacity = ret as u32;
+
+        Ok(pipe)
+    }
+
+    pub fn read_fd(&self) -> RawFd {
+        self.fds[0]
+    }
+
+    pub fn write_fd(&self) -> RawFd {
+        self.fds[1]
+    }
+
+    /// Returns how many bytes the pipe can hold.
+    pub fn capacity(&self) -> u32 {
+        self.capacity
+    }
+
+    /// Returns how many bytes can be spliced into the pipe at once from any file offset.
+    ///
+    /// The pipe holds whole pages, so an unaligned file range may take one more page.
+    pub fn max_chunk(&self) -> u32 {
+        let page_size = unsafe { libc::sysconf(libc::_SC_PAGESIZE) } as u32;
+        self.capacity.saturating_sub(page_size)
+    }
+
+    /// Returns how many bytes are left in the pipe.
+    pub fn pending(&self) -> io::Result<u32> {
+        let mut n: libc::c_int = 0;
+        let ret = unsafe { libc::ioctl(self.read_fd(), libc::FIONREAD, &mut n) };
+        if ret < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(n as u32)
+    }
+}
+
+impl Drop for SplicePipe {
+    fn drop(&mut self) {
+        unsafe {
+            libc::close(self.fds[0]);
+            libc::close(self.fds[1]);
+        }
+    }
+}
+
+impl fmt::Debug for SplicePipe {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SplicePipe")
+            .field("read_fd", &self.read_fd())
+            .field("write_fd", &self.write_fd())
+            .field("capacity", &self.capacity)
+            .finish()
+    }
+}
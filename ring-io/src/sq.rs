@@ -1,5 +1,6 @@
 use crate::ring::{RawRing, RawRingPtr};
-use crate::sqe::{PrepareSqe, SQE};
+use crate::splice::SplicePipe;
+use crate::sqe::{PrepareSqe, SpliceFlags, SubmissionFlags, Target, SQE};
 use crate::{sys, utils};
 
 use std::mem::{self, MaybeUninit};
@@ -44,6 +45,57 @@ impl SubmissionQueue<'_> {
         unsafe { self.get_sqe_uninit().map(|sqe| sqe.prep_nop()) }
     }
 
+    /// Prepares two linked splice requests which move `n_bytes` from `fd_in` to `fd_out` through `pipe`.
+    ///
+    /// At most [`SplicePipe::max_chunk`] bytes are moved.
+    /// Returns the number of bytes which the requests try to move,
+    /// or `None` if there are less than two available SQEs.
+    ///
+    /// Both SQEs have `user_data`. The CQE result of the first one is the number of bytes
+    /// spliced into the pipe, and the result of the second one is the number of bytes sent.
+    ///
+    /// If the first request moves less bytes than expected, for example at the end of file,
+    /// the second one fails with `ECANCELED`.
+    /// If the second request sends less bytes than the first one moved,
+    /// for example when the socket buffer is full, the rest stays in the pipe.
+    /// In both cases [`SplicePipe::pending`] reports the bytes left in the pipe.
+    /// They must be spliced from [`SplicePipe::read_fd`] to `fd_out`
+    /// before the pipe is used again, otherwise the pipe must be dropped.
+    ///
+    /// # Safety
+    /// `pipe` must be empty and must not be used by other requests until both CQEs have been reaped.
+    ///
+    /// See [`SQE`]
+    pub unsafe fn prep_sendfile(
+        &mut self,
+        pipe: &SplicePipe,
+        fd_in: impl Into<Target>,
+        off_in: Option<u64>,
+        fd_out: impl Into<Target>,
+        n_bytes: u32,
+        user_data: u64,
+    ) -> Option<u32> {
+        if self.space_left() < 2 {
+            return None;
+        }
+
+        let n_bytes = n_bytes.min(pipe.max_chunk());
+        let flags = SpliceFlags::MOVE;
+
+        let (pipe_in, pipe_out) = (pipe.read_fd(), pipe.write_fd());
+
+        let sqe = self.get_sqe_uninit()?;
+        let sqe = sqe.prep_splice(fd_in, off_in, pipe_out, None, n_bytes, flags);
+        sqe.enable_flags(SubmissionFlags::IO_LINK);
+        sqe.set_user_data(user_data);
+
+        let sqe = self.get_sqe_uninit()?;
+        let sqe = sqe.prep_splice(pipe_in, None, fd_out, None, n_bytes, flags);
+        sqe.set_user_data(user_data);
+
+        Some(n_bytes)
+    }
+
     pub fn submit(&mut self) -> io::Result<u32> {
         unsafe {
             let ring_ptr = self.ring.get_mut_ptr();
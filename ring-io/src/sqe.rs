@@ -1,7 +1,14 @@
+use crate::cqe::ZcTracker;
+use crate::file_table::FixedFd;
+use crate::fixed_buf::FixedBuf;
 use crate::sys;
 
-use std::mem::MaybeUninit;
-use std::os::unix::io::RawFd;
+use std::ffi::CStr;
+use std::mem::{self, MaybeUninit};
+pub use std::net::Shutdown;
+use std::ops::Range;
+use std::os::unix::io::{AsRawFd, BorrowedFd, RawFd};
+use std::time::Duration;
 use std::{fmt, ptr};
 
 use bitflags::bitflags;
@@ -18,187 +25,2678 @@ unsafe impl Sync for SQE {}
 
 impl fmt::Debug for SQE {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "SQE {{ .. }}")
+        let layout = SqeLayout::of(self.opcode());
+
+        let mut d = f.debug_struct("SQE");
+        match self.opcode() {
+            Some(op) => d.field("opcode", &op),
+            None => d.field("opcode", &self.raw_opcode()),
+        };
+        d.field("flags", &self.flags());
+
+        let fields: [(Option<&str>, &dyn fmt::Debug); 8] = [
+            (layout.fd, &self.fd()),
+            (layout.off, &self.off()),
+            (layout.addr, &Hex(self.addr())),
+            (layout.len, &self.len()),
+            (layout.op_flags, &Hex(u64::from(self.raw_op_flags()))),
+            (layout.buf_index, &self.buf_index()),
+            (layout.fd_in, &self.raw_splice_fd_in()),
+            (layout.addr3, &Hex(self.raw_addr3())),
+        ];
+        for (name, value) in fields.iter() {
+            if let Some(name) = name {
+                d.field(name, value);
+            }
+        }
+
+        if self.personality() != 0 {
+            d.field("personality", &self.personality());
+        }
+        d.field("user_data", &self.user_data());
+        d.finish()
+    }
+}
+
+struct Hex(u64);
+
+impl fmt::Debug for Hex {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:#x}", self.0)
+    }
+}
+
+/// The meaning of the overloaded SQE fields for an opcode.
+///
+/// `None` means that the field is not used by the opcode.
+#[derive(Clone, Copy)]
+struct SqeLayout {
+    fd: Option<&'static str>,
+    off: Option<&'static str>,
+    addr: Option<&'static str>,
+    len: Option<&'static str>,
+    op_flags: Option<&'static str>,
+    buf_index: Option<&'static str>,
+    fd_in: Option<&'static str>,
+    addr3: Option<&'static str>,
+}
+
+impl SqeLayout {
+    const NONE: Self = Self {
+        fd: None,
+        off: None,
+        addr: None,
+        len: None,
+        op_flags: None,
+        buf_index: None,
+        fd_in: None,
+        addr3: None,
+    };
+
+    const RAW: Self = Self {
+        fd: Some("fd"),
+        off: Some("off"),
+        addr: Some("addr"),
+        len: Some("len"),
+        op_flags: Some("op_flags"),
+        buf_index: Some("buf_index"),
+        fd_in: Some("splice_fd_in"),
+        addr3: Some("addr3"),
+    };
+
+    const RW: Self = Self {
+        fd: Some("fd"),
+        off: Some("offset"),
+        addr: Some("buf"),
+        len: Some("n_bytes"),
+        op_flags: Some("rw_flags"),
+        ..Self::NONE
+    };
+
+    const RWV: Self = Self {
+        addr: Some("iovecs"),
+        len: Some("n_vecs"),
+        ..Self::RW
+    };
+
+    const XATTR: Self = Self {
+        addr: Some("name"),
+        off: Some("value"),
+        len: Some("len"),
+        op_flags: Some("xattr_flags"),
+        ..Self::NONE
+    };
+
+    const FUTEX: Self = Self {
+        fd: Some("futex_flags"),
+        addr: Some("futex"),
+        off: Some("val"),
+        addr3: Some("mask"),
+        ..Self::NONE
+    };
+
+    fn of(op: Option<Opcode>) -> Self {
+        let fd = Some("fd");
+        let op = match op {
+            Some(op) => op,
+            None => return Self::RAW,
+        };
+        match op {
+            Opcode::Nop => Self::NONE,
+            Opcode::Readv | Opcode::Writev => Self::RWV,
+            Opcode::Read | Opcode::Write => Self {
+                buf_index: Some("buf_group"),
+                ..Self::RW
+            },
+            Opcode::ReadFixed | Opcode::WriteFixed => Self {
+                buf_index: Some("buf_index"),
+                ..Self::RW
+            },
+            Opcode::ReadvFixed | Opcode::WritevFixed => Self {
+                buf_index: Some("buf_index"),
+                ..Self::RWV
+            },
+            Opcode::ReadMultishot => Self {
+                buf_index: Some("buf_group"),
+                ..Self::RW
+            },
+            Opcode::Fsync => Self {
+                fd,
+                off: Some("offset"),
+                len: Some("len"),
+                op_flags: Some("fsync_flags"),
+                ..Self::NONE
+            },
+            Opcode::PollAdd => Self {
+                fd,
+                len: Some("poll_flags"),
+                op_flags: Some("poll_events"),
+                ..Self::NONE
+            },
+            Opcode::PollRemove => Self {
+                addr: Some("target_user_data"),
+                off: Some("new_user_data"),
+                len: Some("poll_flags"),
+                op_flags: Some("poll_events"),
+                ..Self::NONE
+            },
+            Opcode::SyncFileRange => Self {
+                fd,
+                off: Some("offset"),
+                len: Some("len"),
+                op_flags: Some("sync_range_flags"),
+                ..Self::NONE
+            },
+            Opcode::Sendmsg | Opcode::Recvmsg | Opcode::SendmsgZc => Self {
+                fd,
+                addr: Some("msghdr"),
+                op_flags: Some("msg_flags"),
+                buf_index: Some("buf_group"),
+                ..Self::NONE
+            },
+            Opcode::Timeout => Self {
+                addr: Some("timespec"),
+                off: Some("count"),
+                op_flags: Some("timeout_flags"),
+                ..Self::NONE
+            },
+            Opcode::TimeoutRemove => Self {
+                addr: Some("target_user_data"),
+                off: Some("timespec"),
+                op_flags: Some("timeout_flags"),
+                ..Self::NONE
+            },
+            Opcode::LinkTimeout => Self {
+                addr: Some("timespec"),
+                op_flags: Some("timeout_flags"),
+                ..Self::NONE
+            },
+            Opcode::Accept => Self {
+                fd,
+                addr: Some("addr"),
+                off: Some("addrlen"),
+                op_flags: Some("accept_flags"),
+                fd_in: Some("file_index"),
+                ..Self::NONE
+            },
+            Opcode::AsyncCancel => Self {
+                fd,
+                addr: Some("target_user_data"),
+                op_flags: Some("cancel_flags"),
+                ..Self::NONE
+            },
+            Opcode::Connect | Opcode::Bind => Self {
+                fd,
+                addr: Some("addr"),
+                off: Some("addrlen"),
+                ..Self::NONE
+            },
+            Opcode::Listen => Self {
+                fd,
+                len: Some("backlog"),
+                ..Self::NONE
+            },
+            Opcode::Fallocate => Self {
+                fd,
+                off: Some("offset"),
+                addr: Some("len"),
+                len: Some("mode"),
+                ..Self::NONE
+            },
+            Opcode::Openat => Self {
+                fd: Some("dirfd"),
+                addr: Some("path"),
+                len: Some("mode"),
+                op_flags: Some("open_flags"),
+                fd_in: Some("file_index"),
+                ..Self::NONE
+            },
+            Opcode::Openat2 => Self {
+                fd: Some("dirfd"),
+                addr: Some("path"),
+                off: Some("how"),
+                len: Some("how_size"),
+                fd_in: Some("file_index"),
+                ..Self::NONE
+            },
+            Opcode::Close => Self {
+                fd,
+                fd_in: Some("file_index"),
+                ..Self::NONE
+            },
+            Opcode::FilesUpdate => Self {
+                addr: Some("fds"),
+                len: Some("n_fds"),
+                off: Some("offset"),
+                ..Self::NONE
+            },
+            Opcode::Statx => Self {
+                fd: Some("dirfd"),
+                addr: Some("path"),
+                len: Some("mask"),
+                off: Some("statxbuf"),
+                op_flags: Some("statx_flags"),
+                ..Self::NONE
+            },
+            Opcode::Fadvise => Self {
+                fd,
+                off: Some("offset"),
+                len: Some("len"),
+                op_flags: Some("advice"),
+                ..Self::NONE
+            },
+            Opcode::Madvise => Self {
+                addr: Some("addr"),
+                len: Some("len"),
+                op_flags: Some("advice"),
+                ..Self::NONE
+            },
+            Opcode::Send | Opcode::Recv => Self {
+                fd,
+                addr: Some("buf"),
+                len: Some("n_bytes"),
+                op_flags: Some("msg_flags"),
+                buf_index: Some("buf_group"),
+                ..Self::NONE
+            },
+            Opcode::SendZc => Self {
+                fd,
+                addr: Some("buf"),
+                len: Some("n_bytes"),
+                off: Some("dest_addr"),
+                op_flags: Some("msg_flags"),
+                buf_index: Some("buf_index"),
+                ..Self::NONE
+            },
+            Opcode::EpollCtl => Self {
+                fd: Some("epfd"),
+                off: Some("fd"),
+                len: Some("op"),
+                addr: Some("event"),
+                ..Self::NONE
+            },
+            Opcode::Splice => Self {
+                fd: Some("fd_out"),
+                off: Some("off_out"),
+                addr: Some("off_in"),
+                len: Some("n_bytes"),
+                op_flags: Some("splice_flags"),
+                fd_in: Some("fd_in"),
+                ..Self::NONE
+            },
+            Opcode::Tee => Self {
+                fd: Some("fd_out"),
+                len: Some("n_bytes"),
+                op_flags: Some("splice_flags"),
+                fd_in: Some("fd_in"),
+                ..Self::NONE
+            },
+            Opcode::ProvideBuffers => Self {
+                fd: Some("n_bufs"),
+                addr: Some("addr"),
+                len: Some("buf_len"),
+                off: Some("bid"),
+                buf_index: Some("buf_group"),
+                ..Self::NONE
+            },
+            Opcode::RemoveBuffers => Self {
+                fd: Some("n_bufs"),
+                buf_index: Some("buf_group"),
+                ..Self::NONE
+            },
+            Opcode::Shutdown => Self {
+                fd,
+                len: Some("how"),
+                ..Self::NONE
+            },
+            Opcode::Renameat => Self {
+                fd: Some("old_dirfd"),
+                addr: Some("old_path"),
+                len: Some("new_dirfd"),
+                off: Some("new_path"),
+                op_flags: Some("rename_flags"),
+                ..Self::NONE
+            },
+            Opcode::Unlinkat => Self {
+                fd: Some("dirfd"),
+                addr: Some("path"),
+                op_flags: Some("unlink_flags"),
+                ..Self::NONE
+            },
+            Opcode::Mkdirat => Self {
+                fd: Some("dirfd"),
+                addr: Some("path"),
+                len: Some("mode"),
+                ..Self::NONE
+            },
+            Opcode::Symlinkat => Self {
+                fd: Some("new_dirfd"),
+                addr: Some("target"),
+                off: Some("link_path"),
+                ..Self::NONE
+            },
+            Opcode::Linkat => Self {
+                fd: Some("old_dirfd"),
+                addr: Some("old_path"),
+                len: Some("new_dirfd"),
+                off: Some("new_path"),
+                op_flags: Some("hardlink_flags"),
+                ..Self::NONE
+            },
+            Opcode::MsgRing => Self {
+                fd: Some("ring_fd"),
+                addr: Some("cmd"),
+                len: Some("res"),
+                off: Some("target_user_data"),
+                op_flags: Some("msg_ring_flags"),
+                fd_in: Some("file_index"),
+                addr3: Some("source_fd"),
+                ..Self::NONE
+            },
+            Opcode::Fsetxattr | Opcode::Fgetxattr => Self { fd, ..Self::XATTR },
+            Opcode::Setxattr | Opcode::Getxattr => Self {
+                addr3: Some("path"),
+                ..Self::XATTR
+            },
+            Opcode::Socket => Self {
+                fd: Some("domain"),
+                off: Some("type"),
+                len: Some("protocol"),
+                fd_in: Some("file_index"),
+                ..Self::NONE
+            },
+            Opcode::Waitid => Self {
+                fd: Some("id"),
+                len: Some("idtype"),
+                off: Some("infop"),
+                fd_in: Some("options"),
+                ..Self::NONE
+            },
+            Opcode::FutexWait | Opcode::FutexWake => Self::FUTEX,
+            Opcode::FutexWaitv => Self {
+                addr: Some("futexv"),
+                len: Some("nr_futex"),
+                ..Self::NONE
+            },
+            Opcode::FixedFdInstall => Self {
+                fd,
+                op_flags: Some("install_flags"),
+                ..Self::NONE
+            },
+            Opcode::Ftruncate => Self {
+                fd,
+                off: Some("len"),
+                ..Self::NONE
+            },
+            Opcode::UringCmd | Opcode::RecvZc | Opcode::EpollWait | Opcode::Pipe => Self::RAW,
+        }
+    }
+}
+
+impl SQE {
+    // --- getters ---
+
+    pub fn raw_opcode(&self) -> u8 {
+        self.sqe.opcode
+    }
+
+    /// Returns `None` if the opcode is unknown to ring-io.
+    pub fn opcode(&self) -> Option<Opcode> {
+        Opcode::from_u8(self.sqe.opcode)
+    }
+
+    /// Returns the flags of the SQE.
+    ///
+    /// Unknown flag bits are ignored.
+    pub fn flags(&self) -> SubmissionFlags {
+        SubmissionFlags::from_bits_truncate(self.sqe.flags)
+    }
+
+    pub fn fd(&self) -> RawFd {
+        self.sqe.fd
+    }
+
+    /// Returns the file offset, which is `addr2` for some opcodes.
+    pub fn off(&self) -> u64 {
+        unsafe { self.sqe.off_addr2.off }
+    }
+
+    pub fn addr(&self) -> u64 {
+        self.sqe.addr
+    }
+
+    #[allow(clippy::len_without_is_empty)]
+    pub fn len(&self) -> u32 {
+        self.sqe.len
+    }
+
+    /// Returns the opcode-specific flags, such as `rw_flags` and `fsync_flags`.
+    pub fn raw_op_flags(&self) -> u32 {
+        unsafe { self.sqe.cmd_flags.fsync_flags }
+    }
+
+    /// Returns the I/O priority, which holds opcode-specific flags for some opcodes.
+    pub fn raw_ioprio(&self) -> u16 {
+        self.sqe.ioprio
+    }
+
+    pub fn user_data(&self) -> u64 {
+        self.sqe.user_data
+    }
+
+    /// Returns the index of the fixed buffer, which shares its storage with the buffer group.
+    pub fn buf_index(&self) -> u16 {
+        unsafe { self.sqe.buf_index.buf_index.index_or_group }
+    }
+
+    /// Returns the buffer group, which shares its storage with the fixed buffer index.
+    pub fn buf_group(&self) -> u16 {
+        self.buf_index()
+    }
+
+    pub fn personality(&self) -> u16 {
+        unsafe { self.sqe.buf_index.buf_index.personality }
+    }
+
+    /// Returns `splice_fd_in`, which is `file_index` for some opcodes.
+    pub fn raw_splice_fd_in(&self) -> i32 {
+        unsafe { self.sqe.buf_index.buf_index.splice_fd_in }
+    }
+
+    pub fn raw_addr3(&self) -> u64 {
+        unsafe { self.sqe.buf_index.__pad2[1] }
+    }
+
+    // --- methods ---
+
+    pub fn new_uninit() -> MaybeUninit<Self> {
+        MaybeUninit::uninit()
+    }
+
+    pub fn overwrite_uninit(this: &mut MaybeUninit<SQE>, sqe: SQE) -> &mut Self {
+        unsafe {
+            ptr::write(this.as_mut_ptr(), sqe);
+            &mut *this.as_mut_ptr()
+        }
+    }
+
+    /// Replaces the flags, except that [`SubmissionFlags::FIXED_FILE`] set by the prep method
+    /// for a [`Target::Fixed`] is kept.
+    ///
+    /// [`SubmissionFlags::FIXED_FILE`] in `flags` is honoured, so a registered file index
+    /// can still be passed to a prep method as a raw fd.
+    pub fn set_flags(&mut self, flags: SubmissionFlags) {
+        self.sqe.flags = flags.bits() | (self.sqe.flags & sys::IOSQE_FIXED_FILE);
+    }
+
+    pub fn enable_flags(&mut self, flags: SubmissionFlags) {
+        self.sqe.flags |= flags.bits()
+    }
+
+    pub fn set_user_data(&mut self, user_data: u64) {
+        self.sqe.user_data = user_data;
+    }
+
+    /// Sets the flags of a read or write request.
+    ///
+    /// It must be called after the prep method, which clears the flags.
+    pub fn set_rw_flags(&mut self, flags: RwFlags) {
+        self.sqe.cmd_flags.rw_flags = flags.bits();
+    }
+
+    /// Sets the I/O priority of a read or write request.
+    ///
+    /// It must be called after the prep method, which clears the priority.
+    pub fn set_ioprio(&mut self, prio: IoPriority) {
+        self.sqe.ioprio = prio.raw();
+    }
+
+    /// Lets the kernel select a buffer from the group `buf_group` when the IO is ready,
+    /// and enables [`SubmissionFlags::BUFFER_SELECT`].
+    ///
+    /// The buffer pointer of the request should be null and the length is the maximum length to use.
+    /// The selected buffer is returned by [`CQE::buffer_id`](crate::cqe::CQE::buffer_id).
+    pub fn set_buf_group(&mut self, buf_group: u16) {
+        self.sqe.buf_index.buf_index.index_or_group = buf_group;
+        self.enable_flags(SubmissionFlags::BUFFER_SELECT);
+    }
+}
+
+bitflags! {
+    pub struct SubmissionFlags: u8 {
+        const FIXED_FILE    = sys::IOSQE_FIXED_FILE;
+        const IO_DRAIN      = sys::IOSQE_IO_DRAIN;
+        const IO_LINK       = sys::IOSQE_IO_LINK;
+        const IO_HARDLINK   = sys::IOSQE_IO_HARDLINK;
+        const ASYNC         = sys::IOSQE_ASYNC;
+        const BUFFER_SELECT = sys::IOSQE_BUFFER_SELECT;
+    }
+}
+
+/// The file which a request operates on.
+///
+/// Prep methods set [`SubmissionFlags::FIXED_FILE`] for a [`Target::Fixed`] automatically.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Target {
+    /// A file descriptor of the process.
+    Fd(RawFd),
+    /// An index into the registered files.
+    Fixed(u32),
+}
+
+impl Target {
+    pub fn is_fixed(self) -> bool {
+        matches!(self, Self::Fixed(_))
+    }
+
+    pub(crate) fn raw(self) -> RawFd {
+        match self {
+            Self::Fd(fd) => fd,
+            Self::Fixed(index) => index as RawFd,
+        }
+    }
+}
+
+impl From<RawFd> for Target {
+    fn from(fd: RawFd) -> Self {
+        Self::Fd(fd)
+    }
+}
+
+impl From<BorrowedFd<'_>> for Target {
+    fn from(fd: BorrowedFd<'_>) -> Self {
+        Self::Fd(fd.as_raw_fd())
+    }
+}
+
+impl<T: AsRawFd> From<&T> for Target {
+    fn from(file: &T) -> Self {
+        Self::Fd(file.as_raw_fd())
+    }
+}
+
+impl From<FixedFd> for Target {
+    fn from(fixed: FixedFd) -> Self {
+        Self::Fixed(fixed.index())
+    }
+}
+
+bitflags! {
+    /// Flags for read and write requests. See `preadv2(2)`.
+    pub struct RwFlags: i32 {
+        const HIPRI  = libc::RWF_HIPRI;
+        const DSYNC  = libc::RWF_DSYNC;
+        const SYNC   = libc::RWF_SYNC;
+        const NOWAIT = libc::RWF_NOWAIT;
+        const APPEND = libc::RWF_APPEND;
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(u16)]
+pub enum IoPriorityClass {
+    None = 0,
+    RealTime = 1,
+    BestEffort = 2,
+    Idle = 3,
+}
+
+/// An I/O priority, which consists of a class and a level. See `ioprio_set(2)`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct IoPriority(u16);
+
+impl IoPriority {
+    const CLASS_SHIFT: u16 = 13;
+    const LEVEL_MASK: u16 = (1 << Self::CLASS_SHIFT) - 1;
+
+    /// # Panics
+    /// Panics if `level` is greater than 7.
+    pub fn new(class: IoPriorityClass, level: u8) -> Self {
+        assert!(level < 8);
+        Self((class as u16) << Self::CLASS_SHIFT | u16::from(level))
+    }
+
+    pub fn from_raw(raw: u16) -> Self {
+        Self(raw)
+    }
+
+    pub fn raw(self) -> u16 {
+        self.0
+    }
+
+    /// Returns `None` if the class is unknown.
+    pub fn class(self) -> Option<IoPriorityClass> {
+        match self.0 >> Self::CLASS_SHIFT {
+            0 => Some(IoPriorityClass::None),
+            1 => Some(IoPriorityClass::RealTime),
+            2 => Some(IoPriorityClass::BestEffort),
+            3 => Some(IoPriorityClass::Idle),
+            _ => None,
+        }
+    }
+
+    pub fn level(self) -> u16 {
+        self.0 & Self::LEVEL_MASK
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(u8)]
+#[non_exhaustive]
+pub enum Opcode {
+    Nop = sys::IoRingOp::IORING_OP_NOP as u8,
+    Readv = sys::IoRingOp::IORING_OP_READV as u8,
+    Writev = sys::IoRingOp::IORING_OP_WRITEV as u8,
+    Fsync = sys::IoRingOp::IORING_OP_FSYNC as u8,
+    ReadFixed = sys::IoRingOp::IORING_OP_READ_FIXED as u8,
+    WriteFixed = sys::IoRingOp::IORING_OP_WRITE_FIXED as u8,
+    PollAdd = sys::IoRingOp::IORING_OP_POLL_ADD as u8,
+    PollRemove = sys::IoRingOp::IORING_OP_POLL_REMOVE as u8,
+    SyncFileRange = sys::IoRingOp::IORING_OP_SYNC_FILE_RANGE as u8,
+    Sendmsg = sys::IoRingOp::IORING_OP_SENDMSG as u8,
+    Recvmsg = sys::IoRingOp::IORING_OP_RECVMSG as u8,
+    Timeout = sys::IoRingOp::IORING_OP_TIMEOUT as u8,
+    TimeoutRemove = sys::IoRingOp::IORING_OP_TIMEOUT_REMOVE as u8,
+    Accept = sys::IoRingOp::IORING_OP_ACCEPT as u8,
+    AsyncCancel = sys::IoRingOp::IORING_OP_ASYNC_CANCEL as u8,
+    LinkTimeout = sys::IoRingOp::IORING_OP_LINK_TIMEOUT as u8,
+    Connect = sys::IoRingOp::IORING_OP_CONNECT as u8,
+    Fallocate = sys::IoRingOp::IORING_OP_FALLOCATE as u8,
+    Openat = sys::IoRingOp::IORING_OP_OPENAT as u8,
+    Close = sys::IoRingOp::IORING_OP_CLOSE as u8,
+    FilesUpdate = sys::IoRingOp::IORING_OP_FILES_UPDATE as u8,
+    Statx = sys::IoRingOp::IORING_OP_STATX as u8,
+    Read = sys::IoRingOp::IORING_OP_READ as u8,
+    Write = sys::IoRingOp::IORING_OP_WRITE as u8,
+    Fadvise = sys::IoRingOp::IORING_OP_FADVISE as u8,
+    Madvise = sys::IoRingOp::IORING_OP_MADVISE as u8,
+    Send = sys::IoRingOp::IORING_OP_SEND as u8,
+    Recv = sys::IoRingOp::IORING_OP_RECV as u8,
+    Openat2 = sys::IoRingOp::IORING_OP_OPENAT2 as u8,
+    EpollCtl = sys::IoRingOp::IORING_OP_EPOLL_CTL as u8,
+    Splice = sys::IoRingOp::IORING_OP_SPLICE as u8,
+    ProvideBuffers = sys::IoRingOp::IORING_OP_PROVIDE_BUFFERS as u8,
+    RemoveBuffers = sys::IoRingOp::IORING_OP_REMOVE_BUFFERS as u8,
+    Tee = sys::IoRingOp::IORING_OP_TEE as u8,
+    Shutdown = sys::IORING_OP_SHUTDOWN,
+    Renameat = sys::IORING_OP_RENAMEAT,
+    Unlinkat = sys::IORING_OP_UNLINKAT,
+    Mkdirat = sys::IORING_OP_MKDIRAT,
+    Symlinkat = sys::IORING_OP_SYMLINKAT,
+    Linkat = sys::IORING_OP_LINKAT,
+    MsgRing = sys::IORING_OP_MSG_RING,
+    Fsetxattr = sys::IORING_OP_FSETXATTR,
+    Setxattr = sys::IORING_OP_SETXATTR,
+    Fgetxattr = sys::IORING_OP_FGETXATTR,
+    Getxattr = sys::IORING_OP_GETXATTR,
+    Socket = sys::IORING_OP_SOCKET,
+    UringCmd = sys::IORING_OP_URING_CMD,
+    SendZc = sys::IORING_OP_SEND_ZC,
+    SendmsgZc = sys::IORING_OP_SENDMSG_ZC,
+    ReadMultishot = sys::IORING_OP_READ_MULTISHOT,
+    Waitid = sys::IORING_OP_WAITID,
+    FutexWait = sys::IORING_OP_FUTEX_WAIT,
+    FutexWake = sys::IORING_OP_FUTEX_WAKE,
+    FutexWaitv = sys::IORING_OP_FUTEX_WAITV,
+    FixedFdInstall = sys::IORING_OP_FIXED_FD_INSTALL,
+    Ftruncate = sys::IORING_OP_FTRUNCATE,
+    Bind = sys::IORING_OP_BIND,
+    Listen = sys::IORING_OP_LISTEN,
+    RecvZc = sys::IORING_OP_RECV_ZC,
+    EpollWait = sys::IORING_OP_EPOLL_WAIT,
+    ReadvFixed = sys::IORING_OP_READV_FIXED,
+    WritevFixed = sys::IORING_OP_WRITEV_FIXED,
+    Pipe = sys::IORING_OP_PIPE,
+}
+
+impl Opcode {
+    /// All opcodes, indexed by their values.
+    const ALL: [Opcode; 63] = [
+        Opcode::Nop,
+        Opcode::Readv,
+        Opcode::Writev,
+        Opcode::Fsync,
+        Opcode::ReadFixed,
+        Opcode::WriteFixed,
+        Opcode::PollAdd,
+        Opcode::PollRemove,
+        Opcode::SyncFileRange,
+        Opcode::Sendmsg,
+        Opcode::Recvmsg,
+        Opcode::Timeout,
+        Opcode::TimeoutRemove,
+        Opcode::Accept,
+        Opcode::AsyncCancel,
+        Opcode::LinkTimeout,
+        Opcode::Connect,
+        Opcode::Fallocate,
+        Opcode::Openat,
+        Opcode::Close,
+        Opcode::FilesUpdate,
+        Opcode::Statx,
+        Opcode::Read,
+        Opcode::Write,
+        Opcode::Fadvise,
+        Opcode::Madvise,
+        Opcode::Send,
+        Opcode::Recv,
+        Opcode::Openat2,
+        Opcode::EpollCtl,
+        Opcode::Splice,
+        Opcode::ProvideBuffers,
+        Opcode::RemoveBuffers,
+        Opcode::Tee,
+        Opcode::Shutdown,
+        Opcode::Renameat,
+        Opcode::Unlinkat,
+        Opcode::Mkdirat,
+        Opcode::Symlinkat,
+        Opcode::Linkat,
+        Opcode::MsgRing,
+        Opcode::Fsetxattr,
+        Opcode::Setxattr,
+        Opcode::Fgetxattr,
+        Opcode::Getxattr,
+        Opcode::Socket,
+        Opcode::UringCmd,
+        Opcode::SendZc,
+        Opcode::SendmsgZc,
+        Opcode::ReadMultishot,
+        Opcode::Waitid,
+        Opcode::FutexWait,
+        Opcode::FutexWake,
+        Opcode::FutexWaitv,
+        Opcode::FixedFdInstall,
+        Opcode::Ftruncate,
+        Opcode::Bind,
+        Opcode::Listen,
+        Opcode::RecvZc,
+        Opcode::EpollWait,
+        Opcode::ReadvFixed,
+        Opcode::WritevFixed,
+        Opcode::Pipe,
+    ];
+
+    pub fn from_u8(op: u8) -> Option<Self> {
+        Self::ALL.get(usize::from(op)).copied()
+    }
+}
+
+// `Opcode::ALL` must be indexed by the opcode values
+const _: () = {
+    let mut i = 0;
+    while i < Opcode::ALL.len() {
+        assert!(Opcode::ALL[i] as usize == i);
+        i += 1;
+    }
+};
+
+bitflags! {
+    pub struct FsyncFlags: u32 {
+        const FSYNC_DATASYNC    = sys::IORING_FSYNC_DATASYNC;
+    }
+}
+
+bitflags! {
+    pub struct SyncFileRangeFlags: u32 {
+        const WAIT_BEFORE   = libc::SYNC_FILE_RANGE_WAIT_BEFORE;
+        const WRITE         = libc::SYNC_FILE_RANGE_WRITE;
+        const WAIT_AFTER    = libc::SYNC_FILE_RANGE_WAIT_AFTER;
+    }
+}
+
+bitflags! {
+    pub struct SpliceFlags: u32 {
+        const MOVE          = libc::SPLICE_F_MOVE;
+        const NONBLOCK      = libc::SPLICE_F_NONBLOCK;
+        const MORE          = libc::SPLICE_F_MORE;
+        const GIFT          = libc::SPLICE_F_GIFT;
+        /// `fd_in` is an index into the registered files.
+        const FD_IN_FIXED   = sys::SPLICE_F_FD_IN_FIXED;
+    }
+}
+
+bitflags! {
+    /// The empty set allocates disk space and extends the file size if needed.
+    pub struct FallocateFlags: i32 {
+        const KEEP_SIZE         = libc::FALLOC_FL_KEEP_SIZE;
+        const PUNCH_HOLE        = libc::FALLOC_FL_PUNCH_HOLE;
+        const COLLAPSE_RANGE    = libc::FALLOC_FL_COLLAPSE_RANGE;
+        const ZERO_RANGE        = libc::FALLOC_FL_ZERO_RANGE;
+        const INSERT_RANGE      = libc::FALLOC_FL_INSERT_RANGE;
+        const UNSHARE_RANGE     = libc::FALLOC_FL_UNSHARE_RANGE;
+    }
+}
+
+/// The advice of `posix_fadvise`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(i32)]
+pub enum FileAdvice {
+    Normal = libc::POSIX_FADV_NORMAL,
+    Random = libc::POSIX_FADV_RANDOM,
+    Sequential = libc::POSIX_FADV_SEQUENTIAL,
+    WillNeed = libc::POSIX_FADV_WILLNEED,
+    DontNeed = libc::POSIX_FADV_DONTNEED,
+    NoReuse = libc::POSIX_FADV_NOREUSE,
+}
+
+/// The advice of `madvise`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(i32)]
+#[non_exhaustive]
+pub enum MemoryAdvice {
+    Normal = libc::MADV_NORMAL,
+    Random = libc::MADV_RANDOM,
+    Sequential = libc::MADV_SEQUENTIAL,
+    WillNeed = libc::MADV_WILLNEED,
+    DontNeed = libc::MADV_DONTNEED,
+    Free = libc::MADV_FREE,
+    Remove = libc::MADV_REMOVE,
+    DontFork = libc::MADV_DONTFORK,
+    DoFork = libc::MADV_DOFORK,
+    Mergeable = libc::MADV_MERGEABLE,
+    Unmergeable = libc::MADV_UNMERGEABLE,
+    HugePage = libc::MADV_HUGEPAGE,
+    NoHugePage = libc::MADV_NOHUGEPAGE,
+    DontDump = libc::MADV_DONTDUMP,
+    DoDump = libc::MADV_DODUMP,
+    Cold = libc::MADV_COLD,
+    PageOut = libc::MADV_PAGEOUT,
+}
+
+bitflags! {
+    pub struct TimeoutFlags: u32 {
+        /// The timespec is an absolute time instead of a relative one.
+        const ABS           = sys::IORING_TIMEOUT_ABS;
+        /// Uses `CLOCK_BOOTTIME` instead of `CLOCK_MONOTONIC`.
+        const BOOTTIME      = sys::IORING_TIMEOUT_BOOTTIME;
+        /// Uses `CLOCK_REALTIME` instead of `CLOCK_MONOTONIC`.
+        const REALTIME      = sys::IORING_TIMEOUT_REALTIME;
+        /// Completes with zero instead of `-ETIME` when the timeout expires.
+        const ETIME_SUCCESS = sys::IORING_TIMEOUT_ETIME_SUCCESS;
+    }
+}
+
+/// A timespec which is shared with the kernel.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(C)]
+pub struct Timespec {
+    tv_sec: i64,
+    tv_nsec: i64,
+}
+
+impl Timespec {
+    /// # Panics
+    /// Panics if `nsec` is not less than one billion.
+    pub fn new(sec: i64, nsec: u32) -> Self {
+        assert!(nsec < 1_000_000_000, "nsec must be less than one billion");
+        Self {
+            tv_sec: sec,
+            tv_nsec: i64::from(nsec),
+        }
+    }
+
+    pub fn sec(&self) -> i64 {
+        self.tv_sec
+    }
+
+    pub fn nsec(&self) -> u32 {
+        self.tv_nsec as u32 // safe cast: 0 <= tv_nsec < 1e9
+    }
+
+    fn as_raw_ptr(this: *const Self) -> *mut sys::__kernel_timespec {
+        this as *mut sys::__kernel_timespec
+    }
+}
+
+impl From<Duration> for Timespec {
+    /// Saturates at `i64::MAX` seconds.
+    fn from(d: Duration) -> Self {
+        let sec = d.as_secs().min(i64::MAX as u64) as i64; // safe cast: sec <= i64::MAX
+        Self::new(sec, d.subsec_nanos())
+    }
+}
+
+bitflags! {
+    pub struct CancelFlags: u32 {
+        /// Cancels all matching requests instead of only the first one.
+        const ALL           = sys::IORING_ASYNC_CANCEL_ALL;
+        /// Matches any request, ignoring the user data or the fd.
+        const ANY           = sys::IORING_ASYNC_CANCEL_ANY;
+    }
+}
+
+bitflags! {
+    /// `O_RDONLY` is the empty set.
+    pub struct OpenFlags: i32 {
+        const WRONLY        = libc::O_WRONLY;
+        const RDWR          = libc::O_RDWR;
+        const APPEND        = libc::O_APPEND;
+        const CREAT         = libc::O_CREAT;
+        const EXCL          = libc::O_EXCL;
+        const TRUNC         = libc::O_TRUNC;
+        const CLOEXEC       = libc::O_CLOEXEC;
+        const DIRECT        = libc::O_DIRECT;
+        const DIRECTORY     = libc::O_DIRECTORY;
+        const DSYNC         = libc::O_DSYNC;
+        const SYNC          = libc::O_SYNC;
+        const NOATIME       = libc::O_NOATIME;
+        const NOCTTY        = libc::O_NOCTTY;
+        const NOFOLLOW      = libc::O_NOFOLLOW;
+        const NONBLOCK      = libc::O_NONBLOCK;
+        const PATH          = libc::O_PATH;
+        const TMPFILE       = libc::O_TMPFILE;
+        const LARGEFILE     = libc::O_LARGEFILE;
+    }
+}
+
+bitflags! {
+    pub struct ResolveFlags: u64 {
+        const NO_XDEV       = libc::RESOLVE_NO_XDEV;
+        const NO_MAGICLINKS = libc::RESOLVE_NO_MAGICLINKS;
+        const NO_SYMLINKS   = libc::RESOLVE_NO_SYMLINKS;
+        const BENEATH       = libc::RESOLVE_BENEATH;
+        const IN_ROOT       = libc::RESOLVE_IN_ROOT;
+        const CACHED        = libc::RESOLVE_CACHED;
+    }
+}
+
+/// The arguments of `openat2`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(C)]
+pub struct OpenHow {
+    flags: u64,
+    mode: u64,
+    resolve: u64,
+}
+
+impl OpenHow {
+    pub fn new(flags: OpenFlags) -> Self {
+        Self {
+            flags: flags.bits() as u32 as u64, // zero-extend: flags are non-negative
+            mode: 0,
+            resolve: 0,
+        }
+    }
+
+    /// Sets the mode of the created file, which is valid with `CREAT` or `TMPFILE` only.
+    pub fn mode(mut self, mode: u32) -> Self {
+        self.mode = u64::from(mode);
+        self
+    }
+
+    pub fn resolve(mut self, resolve: ResolveFlags) -> Self {
+        self.resolve = resolve.bits();
+        self
+    }
+}
+
+bitflags! {
+    pub struct StatxFlags: i32 {
+        const EMPTY_PATH        = libc::AT_EMPTY_PATH;
+        const NO_AUTOMOUNT      = libc::AT_NO_AUTOMOUNT;
+        const SYMLINK_NOFOLLOW  = libc::AT_SYMLINK_NOFOLLOW;
+        const FORCE_SYNC        = libc::AT_STATX_FORCE_SYNC;
+        const DONT_SYNC         = libc::AT_STATX_DONT_SYNC;
+    }
+}
+
+bitflags! {
+    pub struct StatxMask: u32 {
+        const TYPE          = libc::STATX_TYPE;
+        const MODE          = libc::STATX_MODE;
+        const NLINK         = libc::STATX_NLINK;
+        const UID           = libc::STATX_UID;
+        const GID           = libc::STATX_GID;
+        const ATIME         = libc::STATX_ATIME;
+        const MTIME         = libc::STATX_MTIME;
+        const CTIME         = libc::STATX_CTIME;
+        const INO           = libc::STATX_INO;
+        const SIZE          = libc::STATX_SIZE;
+        const BLOCKS        = libc::STATX_BLOCKS;
+        const BASIC_STATS   = libc::STATX_BASIC_STATS;
+        const BTIME         = libc::STATX_BTIME;
+        const MNT_ID        = libc::STATX_MNT_ID;
+        const DIOALIGN      = libc::STATX_DIOALIGN;
+    }
+}
+
+/// The result of `statx`.
+///
+/// A field is valid only if its bit is set in [`Statx::mask`].
+#[repr(transparent)]
+pub struct Statx(libc::statx);
+
+impl Statx {
+    pub fn new() -> Self {
+        Self(unsafe { mem::zeroed() })
+    }
+
+    pub fn mask(&self) -> StatxMask {
+        StatxMask::from_bits_truncate(self.0.stx_mask)
+    }
+
+    pub fn blksize(&self) -> u32 {
+        self.0.stx_blksize
+    }
+
+    pub fn attributes(&self) -> u64 {
+        self.0.stx_attributes
+    }
+
+    pub fn nlink(&self) -> u32 {
+        self.0.stx_nlink
+    }
+
+    pub fn uid(&self) -> u32 {
+        self.0.stx_uid
+    }
+
+    pub fn gid(&self) -> u32 {
+        self.0.stx_gid
+    }
+
+    /// Returns the file type and mode.
+    pub fn mode(&self) -> u16 {
+        self.0.stx_mode
+    }
+
+    pub fn is_file(&self) -> bool {
+        u32::from(self.0.stx_mode) & libc::S_IFMT == libc::S_IFREG
+    }
+
+    pub fn is_dir(&self) -> bool {
+        u32::from(self.0.stx_mode) & libc::S_IFMT == libc::S_IFDIR
+    }
+
+    pub fn is_symlink(&self) -> bool {
+        u32::from(self.0.stx_mode) & libc::S_IFMT == libc::S_IFLNK
+    }
+
+    pub fn ino(&self) -> u64 {
+        self.0.stx_ino
+    }
+
+    pub fn size(&self) -> u64 {
+        self.0.stx_size
+    }
+
+    pub fn blocks(&self) -> u64 {
+        self.0.stx_blocks
+    }
+
+    pub fn atime(&self) -> Timespec {
+        Self::timestamp(&self.0.stx_atime)
+    }
+
+    pub fn btime(&self) -> Timespec {
+        Self::timestamp(&self.0.stx_btime)
+    }
+
+    pub fn ctime(&self) -> Timespec {
+        Self::timestamp(&self.0.stx_ctime)
+    }
+
+    pub fn mtime(&self) -> Timespec {
+        Self::timestamp(&self.0.stx_mtime)
+    }
+
+    pub fn rdev(&self) -> (u32, u32) {
+        (self.0.stx_rdev_major, self.0.stx_rdev_minor)
+    }
+
+    pub fn dev(&self) -> (u32, u32) {
+        (self.0.stx_dev_major, self.0.stx_dev_minor)
+    }
+
+    fn timestamp(ts: &libc::statx_timestamp) -> Timespec {
+        Timespec::new(ts.tv_sec, ts.tv_nsec)
+    }
+}
+
+impl Default for Statx {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl fmt::Debug for Statx {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Statx")
+            .field("mask", &self.mask())
+            .field("mode", &format_args!("{:#o}", self.mode()))
+            .field("ino", &self.ino())
+            .field("size", &self.size())
+            .field("nlink", &self.nlink())
+            .field("uid", &self.uid())
+            .field("gid", &self.gid())
+            .field("mtime", &self.mtime())
+            .finish()
+    }
+}
+
+bitflags! {
+    pub struct RenameFlags: u32 {
+        const NOREPLACE     = libc::RENAME_NOREPLACE;
+        const EXCHANGE      = libc::RENAME_EXCHANGE;
+        const WHITEOUT      = libc::RENAME_WHITEOUT;
+    }
+}
+
+bitflags! {
+    pub struct UnlinkFlags: i32 {
+        const REMOVEDIR     = libc::AT_REMOVEDIR;
+    }
+}
+
+bitflags! {
+    pub struct LinkFlags: i32 {
+        const SYMLINK_FOLLOW    = libc::AT_SYMLINK_FOLLOW;
+        const EMPTY_PATH        = libc::AT_EMPTY_PATH;
+    }
+}
+
+bitflags! {
+    pub struct AcceptFlags: i32 {
+        const NONBLOCK      = libc::SOCK_NONBLOCK;
+        const CLOEXEC       = libc::SOCK_CLOEXEC;
+    }
+}
+
+bitflags! {
+    pub struct PollEvents: u32 {
+        const IN            = libc::EPOLLIN as u32;
+        const PRI           = libc::EPOLLPRI as u32;
+        const OUT           = libc::EPOLLOUT as u32;
+        const ERR           = libc::EPOLLERR as u32;
+        const HUP           = libc::EPOLLHUP as u32;
+        const RDNORM        = libc::EPOLLRDNORM as u32;
+        const RDBAND        = libc::EPOLLRDBAND as u32;
+        const WRNORM        = libc::EPOLLWRNORM as u32;
+        const WRBAND        = libc::EPOLLWRBAND as u32;
+        const MSG           = libc::EPOLLMSG as u32;
+        const RDHUP         = libc::EPOLLRDHUP as u32;
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(i32)]
+pub enum EpollCtlOp {
+    Add = libc::EPOLL_CTL_ADD,
+    Mod = libc::EPOLL_CTL_MOD,
+    Del = libc::EPOLL_CTL_DEL,
+}
+
+bitflags! {
+    pub struct MsgFlags: i32 {
+        const OOB           = libc::MSG_OOB;
+        const PEEK          = libc::MSG_PEEK;
+        const DONTROUTE     = libc::MSG_DONTROUTE;
+        const TRUNC         = libc::MSG_TRUNC;
+        const DONTWAIT      = libc::MSG_DONTWAIT;
+        const EOR           = libc::MSG_EOR;
+        const WAITALL       = libc::MSG_WAITALL;
+        const CONFIRM       = libc::MSG_CONFIRM;
+        const ERRQUEUE      = libc::MSG_ERRQUEUE;
+        const NOSIGNAL      = libc::MSG_NOSIGNAL;
+        const MORE          = libc::MSG_MORE;
+        const CMSG_CLOEXEC  = libc::MSG_CMSG_CLOEXEC;
+    }
+}
+
+bitflags! {
+    pub struct XattrFlags: i32 {
+        const CREATE  = libc::XATTR_CREATE;
+        const REPLACE = libc::XATTR_REPLACE;
+    }
+}
+
+bitflags! {
+    /// Flags of a 32-bit futex.
+    pub struct FutexFlags: u32 {
+        const PRIVATE = sys::FUTEX2_PRIVATE;
+    }
+}
+
+/// A futex to wait on with [`PrepareSqe::prep_futex_waitv`].
+#[repr(transparent)]
+#[derive(Clone, Copy)]
+pub struct FutexWaitv(sys::futex_waitv);
+
+impl FutexWaitv {
+    /// Waits on the 32-bit futex at `futex` while it holds `val`.
+    pub fn new(futex: *const u32, val: u32, flags: FutexFlags) -> Self {
+        Self(sys::futex_waitv {
+            val: u64::from(val),
+            uaddr: futex as u64,
+            flags: sys::FUTEX2_SIZE_U32 | flags.bits(),
+            __reserved: 0,
+        })
+    }
+}
+
+impl fmt::Debug for FutexWaitv {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FutexWaitv")
+            .field("val", &self.0.val)
+            .field("uaddr", &Hex(self.0.uaddr))
+            .field("flags", &Hex(u64::from(self.0.flags)))
+            .finish()
+    }
+}
+
+bitflags! {
+    /// Options of a `waitid` request. See `waitid(2)`.
+    pub struct WaitFlags: i32 {
+        const EXITED    = libc::WEXITED;
+        const STOPPED   = libc::WSTOPPED;
+        const CONTINUED = libc::WCONTINUED;
+        const NOHANG    = libc::WNOHANG;
+        const NOWAIT    = libc::WNOWAIT;
+    }
+}
+
+impl PrepareSqe for SQE {
+    fn as_raw_mut_sqe(&mut self) -> *mut SQE {
+        self
+    }
+}
+
+impl PrepareSqe for MaybeUninit<SQE> {
+    fn as_raw_mut_sqe(&mut self) -> *mut SQE {
+        self.as_mut_ptr()
+    }
+}
+
+unsafe fn prep_rw(
+    sqe: *mut sys::io_uring_sqe,
+    op: Opcode,
+    fd: RawFd,
+    addr: *const libc::c_void,
+    len: u32,
+    offset: u64,
+) {
+    sys::io_uring_prep_rw(op as libc::c_int, sqe, fd, addr, len, offset)
+}
+
+/// Sets the opcode-specific flags, such as `rw_flags` and `poll32_events`.
+unsafe fn set_op_flags(sqe: *mut sys::io_uring_sqe, flags: u32) {
+    (*sqe).cmd_flags.fsync_flags = flags;
+}
+
+/// Sets [`SubmissionFlags::FIXED_FILE`] if `target` is a registered file.
+unsafe fn set_target(sqe: *mut sys::io_uring_sqe, target: Target) {
+    if target.is_fixed() {
+        (*sqe).flags |= sys::IOSQE_FIXED_FILE;
+    }
+}
+
+/// Sets `file_index` to install the new file into the registered file `slot`.
+unsafe fn set_file_index(sqe: *mut sys::io_uring_sqe, slot: FixedFd) {
+    // safe cast: the index is below the size of a `FileTable`, which the kernel limits to 2^20 files
+    (*sqe).buf_index.buf_index.splice_fd_in = (slot.index() + 1) as i32;
+}
+
+#[allow(clippy::too_many_arguments)]
+unsafe fn prep_xattr(
+    sqe: *mut sys::io_uring_sqe,
+    op: Opcode,
+    fd: RawFd,
+    path: *const libc::c_char,
+    name: &CStr,
+    value: *const u8,
+    len: u32,
+    flags: XattrFlags,
+) {
+    prep_rw(sqe, op, fd, name.as_ptr().cast(), len, value as u64);
+    set_op_flags(sqe, flags.bits() as u32);
+    (*sqe).buf_index.__pad2[1] = path as u64;
+}
+
+unsafe fn set_poll_events(sqe: *mut sys::io_uring_sqe, events: PollEvents) {
+    let events = events.bits();
+    #[cfg(target_endian = "big")]
+    let events = events.rotate_left(16);
+    set_op_flags(sqe, events)
+}
+
+unsafe fn do_prep(
+    this: &mut (impl PrepareSqe + ?Sized),
+    f: impl FnOnce(*mut sys::io_uring_sqe),
+) -> &mut SQE {
+    let sqe = this.as_raw_mut_sqe();
+    f(sqe.cast());
+    &mut *sqe
+}
+
+pub trait PrepareSqe {
+    fn as_raw_mut_sqe(&mut self) -> *mut SQE;
+
+    /// # Safety
+    /// See [`SQE`]
+    unsafe fn prep_nop(&mut self) -> &mut SQE {
+        do_prep(self, |sqe| sys::io_uring_prep_nop(sqe))
+    }
+
+    /// # Safety
+    /// See [`SQE`]
+    unsafe fn prep_read(
+        &mut self,
+        fd: impl Into<Target>,
+        buf: *mut u8,
+        n_bytes: usize,
+        offset: isize,
+    ) -> &mut SQE {
+        let fd = fd.into();
+        do_prep(self, |sqe| {
+            sys::io_uring_prep_read(
+                sqe,
+                fd.raw(),
+                buf.cast(),
+                n_bytes as u32,
+                offset as libc::off_t,
+            );
+            set_target(sqe, fd);
+        })
+    }
+
+    /// # Safety
+    /// See [`SQE`]
+    unsafe fn prep_readv(
+        &mut self,
+        fd: impl Into<Target>,
+        iovecs: *const libc::iovec,
+        n_vecs: usize,
+        offset: isize,
+    ) -> &mut SQE {
+        let fd = fd.into();
+        do_prep(self, |sqe| {
+            let (n_vecs, offset) = (n_vecs as u32, offset as libc::off_t);
+            sys::io_uring_prep_readv(sqe, fd.raw(), iovecs.cast(), n_vecs, offset);
+            set_target(sqe, fd);
+        })
+    }
+
+    /// # Safety
+    /// See [`SQE`]
+    unsafe fn prep_write(
+        &mut self,
+        fd: impl Into<Target>,
+        buf: *const u8,
+        n_bytes: usize,
+        offset: isize,
+    ) -> &mut SQE {
+        let fd = fd.into();
+        do_prep(self, |sqe| {
+            sys::io_uring_prep_write(
+                sqe,
+                fd.raw(),
+                buf.cast(),
+                n_bytes as u32,
+                offset as libc::off_t,
+            );
+            set_target(sqe, fd);
+        })
+    }
+
+    /// # Safety
+    /// See [`SQE`]
+    unsafe fn prep_writev(
+        &mut self,
+        fd: impl Into<Target>,
+        iovecs: *const libc::iovec,
+        n_vecs: usize,
+        offset: isize,
+    ) -> &mut SQE {
+        let fd = fd.into();
+        do_prep(self, |sqe| {
+            let (n_vecs, offset) = (n_vecs as u32, offset as libc::off_t);
+            sys::io_uring_prep_writev(sqe, fd.raw(), iovecs, n_vecs, offset);
+            set_target(sqe, fd);
+        })
+    }
+
+    /// # Safety
+    /// See [`SQE`]
+    unsafe fn prep_fsync(&mut self, fd: impl Into<Target>, flags: FsyncFlags) -> &mut SQE {
+        let fd = fd.into();
+        do_prep(self, |sqe| {
+            sys::io_uring_prep_fsync(sqe, fd.raw(), flags.bits());
+            set_target(sqe, fd);
+        })
+    }
+
+    /// Reads into the bytes `range` of the registered buffer `buf`.
+    ///
+    /// # Panics
+    /// Panics if `range` is out of the bounds of the buffer.
+    ///
+    /// # Safety
+    /// See [`SQE`]
+    unsafe fn prep_read_fixed(
+        &mut self,
+        fd: impl Into<Target>,
+        buf: &mut FixedBuf<'_, '_>,
+        range: Range<usize>,
+        offset: isize,
+    ) -> &mut SQE {
+        let buf_index = usize::from(buf.index());
+        let buf = &mut buf[range];
+        self.prep_read_fixed_raw(fd, buf.as_mut_ptr(), buf.len(), offset, buf_index)
+    }
+
+    /// Reads up to `n_bytes` into `buf`, which lies in the registered buffer `buf_index`.
+    ///
+    /// # Safety
+    /// See [`SQE`]
+    unsafe fn prep_read_fixed_raw(
+        &mut self,
+        fd: impl Into<Target>,
+        buf: *mut u8,
+        n_bytes: usize,
+        offset: isize,
+        buf_index: usize,
+    ) -> &mut SQE {
+        let fd = fd.into();
+        do_prep(self, |sqe| {
+            sys::io_uring_prep_read_fixed(
+                sqe,
+                fd.raw(),
+                buf.cast(),
+                n_bytes as u32,
+                offset as libc::off_t,
+                buf_index as i32,
+            );
+            set_target(sqe, fd);
+        })
+    }
+
+    /// Writes the bytes `range` of the registered buffer `buf`.
+    ///
+    /// # Panics
+    /// Panics if `range` is out of the bounds of the buffer.
+    ///
+    /// # Safety
+    /// See [`SQE`]
+    unsafe fn prep_write_fixed(
+        &mut self,
+        fd: impl Into<Target>,
+        buf: &FixedBuf<'_, '_>,
+        range: Range<usize>,
+        offset: isize,
+    ) -> &mut SQE {
+        let buf_index = usize::from(buf.index());
+        let buf = &buf[range];
+        self.prep_write_fixed_raw(fd, buf.as_ptr(), buf.len(), offset, buf_index)
+    }
+
+    /// Writes `n_bytes` of `buf`, which lies in the registered buffer `buf_index`.
+    ///
+    /// # Safety
+    /// See [`SQE`]
+    unsafe fn prep_write_fixed_raw(
+        &mut self,
+        fd: impl Into<Target>,
+        buf: *const u8,
+        n_bytes: usize,
+        offset: isize,
+        buf_index: usize,
+    ) -> &mut SQE {
+        let fd = fd.into();
+        do_prep(self, |sqe| {
+            sys::io_uring_prep_write_fixed(
+                sqe,
+                fd.raw(),
+                buf as *mut libc::c_void,
+                n_bytes as u32,
+                offset as libc::off_t,
+                buf_index as i32,
+            );
+            set_target(sqe, fd);
+        })
+    }
+
+    /// # Safety
+    /// See [`SQE`]
+    unsafe fn prep_accept(
+        &mut self,
+        fd: impl Into<Target>,
+        addr: *mut libc::sockaddr,
+        addrlen: *mut libc::socklen_t,
+        flags: AcceptFlags,
+    ) -> &mut SQE {
+        let fd = fd.into();
+        do_prep(self, |sqe| {
+            sys::io_uring_prep_accept(sqe, fd.raw(), addr, addrlen, flags.bits());
+            set_target(sqe, fd);
+        })
+    }
+
+    /// Prepares an `accept` request which installs the new socket into the registered file `slot`.
+    ///
+    /// The CQE result is zero on success.
+    ///
+    /// # Safety
+    /// See [`SQE`]
+    unsafe fn prep_accept_direct(
+        &mut self,
+        fd: impl Into<Target>,
+        addr: *mut libc::sockaddr,
+        addrlen: *mut libc::socklen_t,
+        flags: AcceptFlags,
+        slot: FixedFd,
+    ) -> &mut SQE {
+        self.prep_accept(fd, addr, addrlen, flags);
+        do_prep(self, |sqe| set_file_index(sqe, slot))
+    }
+
+    /// Prepares an `accept` request which posts a CQE for every new connection.
+    ///
+    /// Each CQE result is a new fd.
+    /// The CQE has [`CompletionFlags::MORE`](crate::cqe::CompletionFlags::MORE) set
+    /// unless the request has been terminated.
+    ///
+    /// # Safety
+    /// See [`SQE`]
+    unsafe fn prep_accept_multishot(
+        &mut self,
+        fd: impl Into<Target>,
+        addr: *mut libc::sockaddr,
+        addrlen: *mut libc::socklen_t,
+        flags: AcceptFlags,
+    ) -> &mut SQE {
+        self.prep_accept(fd, addr, addrlen, flags);
+        do_prep(self, |sqe| (*sqe).ioprio |= sys::IORING_ACCEPT_MULTISHOT)
+    }
+
+    /// # Safety
+    /// See [`SQE`]
+    unsafe fn prep_connect(
+        &mut self,
+        fd: impl Into<Target>,
+        addr: *const libc::sockaddr,
+        addrlen: libc::socklen_t,
+    ) -> &mut SQE {
+        let fd = fd.into();
+        do_prep(self, |sqe| {
+            sys::io_uring_prep_connect(sqe, fd.raw(), addr as *mut _, addrlen);
+            set_target(sqe, fd);
+        })
+    }
+
+    /// # Safety
+    /// See [`SQE`]
+    unsafe fn prep_send(
+        &mut self,
+        fd: impl Into<Target>,
+        buf: *const u8,
+        n_bytes: usize,
+        flags: MsgFlags,
+    ) -> &mut SQE {
+        let fd = fd.into();
+        do_prep(self, |sqe| {
+            sys::io_uring_prep_send(sqe, fd.raw(), buf.cast(), n_bytes, flags.bits());
+            set_target(sqe, fd);
+        })
+    }
+
+    /// # Safety
+    /// See [`SQE`]
+    unsafe fn prep_recv(
+        &mut self,
+        fd: impl Into<Target>,
+        buf: *mut u8,
+        n_bytes: usize,
+        flags: MsgFlags,
+    ) -> &mut SQE {
+        let fd = fd.into();
+        do_prep(self, |sqe| {
+            sys::io_uring_prep_recv(sqe, fd.raw(), buf.cast(), n_bytes, flags.bits());
+            set_target(sqe, fd);
+        })
+    }
+
+    /// Prepares a `recv` request which posts a CQE every time data arrives.
+    ///
+    /// Each CQE holds a buffer selected from `buf_group`,
+    /// and has [`CompletionFlags::MORE`](crate::cqe::CompletionFlags::MORE) set
+    /// unless the request has been terminated.
+    ///
+    /// # Safety
+    /// See [`SQE`]
+    unsafe fn prep_recv_multishot(
+        &mut self,
+        fd: impl Into<Target>,
+        flags: MsgFlags,
+        buf_group: u16,
+    ) -> &mut SQE {
+        let sqe = self.prep_recv(fd, ptr::null_mut(), 0, flags);
+        sqe.set_buf_group(buf_group);
+        sqe.sqe.ioprio |= sys::IORING_RECV_MULTISHOT;
+        sqe
+    }
+
+    /// # Safety
+    /// See [`SQE`]
+    unsafe fn prep_sendmsg(
+        &mut self,
+        fd: impl Into<Target>,
+        msg: *const libc::msghdr,
+        flags: MsgFlags,
+    ) -> &mut SQE {
+        let fd = fd.into();
+        do_prep(self, |sqe| {
+            sys::io_uring_prep_sendmsg(sqe, fd.raw(), msg, flags.bits() as u32);
+            set_target(sqe, fd);
+        })
+    }
+
+    /// Prepares a zero-copy `send` request.
+    ///
+    /// The request posts a result CQE, and later a notification CQE
+    /// when the kernel no longer uses the buffer.
+    /// See [`PrepareSqe::prep_send_zc_tracked`] to borrow the buffer until then.
+    ///
+    /// # Safety
+    /// The buffer must stay valid until the notification CQE has been reaped.
+    ///
+    /// See [`SQE`]
+    unsafe fn prep_send_zc(
+        &mut self,
+        fd: impl Into<Target>,
+        buf: *const u8,
+        n_bytes: usize,
+        flags: MsgFlags,
+    ) -> &mut SQE {
+        let fd = fd.into();
+        do_prep(self, |sqe| {
+            prep_rw(sqe, Opcode::SendZc, fd.raw(), buf.cast(), n_bytes as u32, 0);
+            set_op_flags(sqe, flags.bits() as u32);
+            set_target(sqe, fd);
+        })
+    }
+
+    /// Prepares a zero-copy `send` request of `buf` with `user_data`,
+    /// and returns a tracker which borrows `buf` until both CQEs have been handled.
+    ///
+    /// # Safety
+    /// The tracker must be kept until [`ZcTracker::is_done`](ZcTracker::is_done)
+    /// returns true.
+    ///
+    /// See [`SQE`]
+    unsafe fn prep_send_zc_tracked<'b>(
+        &mut self,
+        fd: impl Into<Target>,
+        buf: &'b [u8],
+        flags: MsgFlags,
+        user_data: u64,
+    ) -> ZcTracker<'b> {
+        let sqe = self.prep_send_zc(fd, buf.as_ptr(), buf.len(), flags);
+        sqe.set_user_data(user_data);
+        ZcTracker::new(buf, user_data)
+    }
+
+    /// Prepares a zero-copy `sendmsg` request.
+    ///
+    /// See [`PrepareSqe::prep_send_zc`].
+    ///
+    /// # Safety
+    /// The buffers must stay valid until the notification CQE has been reaped.
+    ///
+    /// See [`SQE`]
+    unsafe fn prep_sendmsg_zc(
+        &mut self,
+        fd: impl Into<Target>,
+        msg: *const libc::msghdr,
+        flags: MsgFlags,
+    ) -> &mut SQE {
+        let fd = fd.into();
+        do_prep(self, |sqe| {
+            prep_rw(sqe, Opcode::SendmsgZc, fd.raw(), msg.cast(), 1, 0);
+            set_op_flags(sqe, flags.bits() as u32);
+            set_target(sqe, fd);
+        })
+    }
+
+    /// # Safety
+    /// See [`SQE`]
+    unsafe fn prep_recvmsg(
+        &mut self,
+        fd: impl Into<Target>,
+        msg: *mut libc::msghdr,
+        flags: MsgFlags,
+    ) -> &mut SQE {
+        let fd = fd.into();
+        do_prep(self, |sqe| {
+            sys::io_uring_prep_recvmsg(sqe, fd.raw(), msg, flags.bits() as u32);
+            set_target(sqe, fd);
+        })
+    }
+
+    /// Prepares a `socket` request whose CQE result is the new fd.
+    ///
+    /// # Safety
+    /// See [`SQE`]
+    unsafe fn prep_socket(&mut self, domain: i32, ty: i32, protocol: i32) -> &mut SQE {
+        do_prep(self, |sqe| {
+            let (ty, protocol) = (ty as u64, protocol as u32);
+            prep_rw(sqe, Opcode::Socket, domain, ptr::null(), protocol, ty);
+        })
+    }
+
+    /// Prepares a `socket` request which installs the new socket into the registered file `slot`.
+    ///
+    /// The CQE result is zero on success.
+    ///
+    /// # Safety
+    /// See [`SQE`]
+    unsafe fn prep_socket_direct(
+        &mut self,
+        domain: i32,
+        ty: i32,
+        protocol: i32,
+        slot: FixedFd,
+    ) -> &mut SQE {
+        self.prep_socket(domain, ty, protocol);
+        do_prep(self, |sqe| set_file_index(sqe, slot))
+    }
+
+    /// # Safety
+    /// See [`SQE`]
+    unsafe fn prep_bind(
+        &mut self,
+        fd: impl Into<Target>,
+        addr: *const libc::sockaddr,
+        addrlen: libc::socklen_t,
+    ) -> &mut SQE {
+        let fd = fd.into();
+        do_prep(self, |sqe| {
+            let addrlen = u64::from(addrlen);
+            prep_rw(sqe, Opcode::Bind, fd.raw(), addr.cast(), 0, addrlen);
+            set_target(sqe, fd);
+        })
+    }
+
+    /// # Safety
+    /// See [`SQE`]
+    unsafe fn prep_listen(&mut self, fd: impl Into<Target>, backlog: u32) -> &mut SQE {
+        let fd = fd.into();
+        do_prep(self, |sqe| {
+            prep_rw(sqe, Opcode::Listen, fd.raw(), ptr::null(), backlog, 0);
+            set_target(sqe, fd);
+        })
+    }
+
+    /// # Safety
+    /// See [`SQE`]
+    unsafe fn prep_shutdown(&mut self, fd: impl Into<Target>, how: Shutdown) -> &mut SQE {
+        let fd = fd.into();
+        let how = match how {
+            Shutdown::Read => libc::SHUT_RD,
+            Shutdown::Write => libc::SHUT_WR,
+            Shutdown::Both => libc::SHUT_RDWR,
+        };
+        do_prep(self, |sqe| {
+            prep_rw(sqe, Opcode::Shutdown, fd.raw(), ptr::null(), how as u32, 0);
+            set_target(sqe, fd);
+        })
+    }
+
+    /// Prepares a one-shot poll request.
+    ///
+    /// The CQE result is the mask of the triggered events.
+    ///
+    /// # Safety
+    /// See [`SQE`]
+    unsafe fn prep_poll_add(&mut self, fd: impl Into<Target>, events: PollEvents) -> &mut SQE {
+        let fd = fd.into();
+        do_prep(self, |sqe| {
+            prep_rw(sqe, Opcode::PollAdd, fd.raw(), ptr::null(), 0, 0);
+            set_target(sqe, fd);
+            set_poll_events(sqe, events);
+        })
+    }
+
+    /// Prepares a poll request which posts a CQE every time the events trigger.
+    ///
+    /// Each CQE has [`CompletionFlags::MORE`](crate::cqe::CompletionFlags::MORE) set
+    /// unless the request has been terminated.
+    ///
+    /// # Safety
+    /// See [`SQE`]
+    unsafe fn prep_poll_multishot(
+        &mut self,
+        fd: impl Into<Target>,
+        events: PollEvents,
+    ) -> &mut SQE {
+        let fd = fd.into();
+        do_prep(self, |sqe| {
+            let len = sys::IORING_POLL_ADD_MULTI;
+            prep_rw(sqe, Opcode::PollAdd, fd.raw(), ptr::null(), len, 0);
+            set_target(sqe, fd);
+            set_poll_events(sqe, events);
+        })
+    }
+
+    /// Prepares a request to remove the poll request which has `target_user_data`.
+    ///
+    /// # Safety
+    /// See [`SQE`]
+    unsafe fn prep_poll_remove(&mut self, target_user_data: u64) -> &mut SQE {
+        do_prep(self, |sqe| {
+            sys::io_uring_prep_poll_remove(sqe, target_user_data as *mut libc::c_void)
+        })
+    }
+
+    /// Prepares a request to update the poll request which has `target_user_data`.
+    ///
+    /// `None` fields are left unchanged.
+    ///
+    /// The kernel parses `new_events` like the events of a poll add,
+    /// which are one-shot unless `multishot` is true.
+    /// Pass true to keep a request added with [`PrepareSqe::prep_poll_multishot`] multishot.
+    /// `multishot` is ignored if `new_events` is `None`.
+    ///
+    /// # Safety
+    /// See [`SQE`]
+    unsafe fn prep_poll_update(
+        &mut self,
+        target_user_data: u64,
+        new_user_data: Option<u64>,
+        new_events: Option<PollEvents>,
+        multishot: bool,
+    ) -> &mut SQE {
+        do_prep(self, |sqe| {
+            let mut len = 0;
+            if new_user_data.is_some() {
+                len |= sys::IORING_POLL_UPDATE_USER_DATA;
+            }
+            if new_events.is_some() {
+                len |= sys::IORING_POLL_UPDATE_EVENTS;
+                if multishot {
+                    len |= sys::IORING_POLL_ADD_MULTI;
+                }
+            }
+            let addr = target_user_data as *const libc::c_void;
+            let off = new_user_data.unwrap_or(0);
+            prep_rw(sqe, Opcode::PollRemove, -1, addr, len, off);
+            set_poll_events(sqe, new_events.unwrap_or_else(PollEvents::empty));
+        })
+    }
+
+    /// Prepares an `epoll_ctl` request on the epoll instance `epfd`.
+    ///
+    /// # Safety
+    /// See [`SQE`]
+    unsafe fn prep_epoll_ctl(
+        &mut self,
+        epfd: RawFd,
+        fd: RawFd,
+        op: EpollCtlOp,
+        event: *const libc::epoll_event,
+    ) -> &mut SQE {
+        do_prep(self, |sqe| {
+            sys::io_uring_prep_epoll_ctl(sqe, epfd, fd, op as i32, event as *mut _)
+        })
+    }
+
+    /// Prepares a timeout which completes after `ts`,
+    /// or after `count` other CQEs have been posted if `count` is not zero.
+    ///
+    /// The CQE result is `-ETIME` if the timeout expired and zero if it was completed by `count`.
+    ///
+    /// # Safety
+    /// See [`SQE`]
+    unsafe fn prep_timeout(
+        &mut self,
+        ts: *const Timespec,
+        count: u32,
+        flags: TimeoutFlags,
+    ) -> &mut SQE {
+        do_prep(self, |sqe| {
+            sys::io_uring_prep_timeout(sqe, Timespec::as_raw_ptr(ts), count, flags.bits())
+        })
+    }
+
+    /// Prepares a request to remove the timeout which has `target_user_data`.
+    ///
+    /// # Safety
+    /// See [`SQE`]
+    unsafe fn prep_timeout_remove(&mut self, target_user_data: u64) -> &mut SQE {
+        do_prep(self, |sqe| {
+            sys::io_uring_prep_timeout_remove(sqe, target_user_data, 0)
+        })
+    }
+
+    /// Prepares a request to update the timeout which has `target_user_data`.
+    ///
+    /// # Safety
+    /// See [`SQE`]
+    unsafe fn prep_timeout_update(
+        &mut self,
+        target_user_data: u64,
+        ts: *const Timespec,
+        flags: TimeoutFlags,
+    ) -> &mut SQE {
+        do_prep(self, |sqe| {
+            let flags = flags.bits() | sys::IORING_TIMEOUT_UPDATE;
+            sys::io_uring_prep_timeout_remove(sqe, target_user_data, flags);
+            (*sqe).off_addr2.addr2 = ts as u64;
+        })
+    }
+
+    /// Prepares a timeout which cancels the previous linked SQE when it expires.
+    ///
+    /// The previous SQE must have [`SubmissionFlags::IO_LINK`] set.
+    ///
+    /// # Safety
+    /// See [`SQE`]
+    unsafe fn prep_link_timeout(&mut self, ts: *const Timespec, flags: TimeoutFlags) -> &mut SQE {
+        do_prep(self, |sqe| {
+            sys::io_uring_prep_link_timeout(sqe, Timespec::as_raw_ptr(ts), flags.bits())
+        })
+    }
+
+    /// Prepares a request to update the linked timeout which has `target_user_data`.
+    ///
+    /// # Safety
+    /// See [`SQE`]
+    unsafe fn prep_link_timeout_update(
+        &mut self,
+        target_user_data: u64,
+        ts: *const Timespec,
+        flags: TimeoutFlags,
+    ) -> &mut SQE {
+        do_prep(self, |sqe| {
+            let flags = flags.bits() | sys::IORING_LINK_TIMEOUT_UPDATE;
+            sys::io_uring_prep_timeout_remove(sqe, target_user_data, flags);
+            (*sqe).off_addr2.addr2 = ts as u64;
+        })
+    }
+
+    /// Prepares a request to cancel the request which has `target_user_data`.
+    ///
+    /// See [`CancelResult`](crate::cqe::CancelResult) for the result.
+    ///
+    /// # Safety
+    /// See [`SQE`]
+    unsafe fn prep_cancel(&mut self, target_user_data: u64, flags: CancelFlags) -> &mut SQE {
+        do_prep(self, |sqe| {
+            let user_data = target_user_data as *mut libc::c_void;
+            sys::io_uring_prep_cancel(sqe, user_data, flags.bits() as i32)
+        })
+    }
+
+    /// Prepares a request to cancel the request which operates on `fd`.
+    ///
+    /// See [`CancelResult`](crate::cqe::CancelResult) for the result.
+    ///
+    /// # Safety
+    /// See [`SQE`]
+    unsafe fn prep_cancel_fd(&mut self, fd: impl Into<Target>, flags: CancelFlags) -> &mut SQE {
+        let fd = fd.into();
+        let mut flags = flags.bits() | sys::IORING_ASYNC_CANCEL_FD;
+        if fd.is_fixed() {
+            flags |= sys::IORING_ASYNC_CANCEL_FD_FIXED;
+        }
+        do_prep(self, |sqe| {
+            prep_rw(sqe, Opcode::AsyncCancel, fd.raw(), ptr::null(), 0, 0);
+            set_op_flags(sqe, flags);
+        })
+    }
+
+    /// Prepares an `openat` request whose CQE result is the new fd.
+    ///
+    /// # Safety
+    /// See [`SQE`]
+    unsafe fn prep_openat(
+        &mut self,
+        dirfd: RawFd,
+        path: &CStr,
+        flags: OpenFlags,
+        mode: u32,
+    ) -> &mut SQE {
+        do_prep(self, |sqe| {
+            sys::io_uring_prep_openat(sqe, dirfd, path.as_ptr(), flags.bits(), mode)
+        })
+    }
+
+    /// Prepares an `openat` request which installs the new file into the registered file `slot`.
+    ///
+    /// The CQE result is zero on success.
+    ///
+    /// # Safety
+    /// See [`SQE`]
+    unsafe fn prep_openat_direct(
+        &mut self,
+        dirfd: RawFd,
+        path: &CStr,
+        flags: OpenFlags,
+        mode: u32,
+        slot: FixedFd,
+    ) -> &mut SQE {
+        self.prep_openat(dirfd, path, flags, mode);
+        do_prep(self, |sqe| set_file_index(sqe, slot))
+    }
+
+    /// Prepares an `openat2` request whose CQE result is the new fd.
+    ///
+    /// # Safety
+    /// See [`SQE`]
+    unsafe fn prep_openat2(&mut self, dirfd: RawFd, path: &CStr, how: *const OpenHow) -> &mut SQE {
+        do_prep(self, |sqe| {
+            sys::io_uring_prep_openat2(sqe, dirfd, path.as_ptr(), how as *mut libc::c_void)
+        })
     }
-}
 
-impl SQE {
-    pub fn new_uninit() -> MaybeUninit<Self> {
-        MaybeUninit::uninit()
+    /// Prepares an `openat2` request which installs the new file into the registered file `slot`.
+    ///
+    /// The CQE result is zero on success.
+    ///
+    /// # Safety
+    /// See [`SQE`]
+    unsafe fn prep_openat2_direct(
+        &mut self,
+        dirfd: RawFd,
+        path: &CStr,
+        how: *const OpenHow,
+        slot: FixedFd,
+    ) -> &mut SQE {
+        self.prep_openat2(dirfd, path, how);
+        do_prep(self, |sqe| set_file_index(sqe, slot))
     }
 
-    pub fn overwrite_uninit(this: &mut MaybeUninit<SQE>, sqe: SQE) -> &mut Self {
-        unsafe {
-            ptr::write(this.as_mut_ptr(), sqe);
-            &mut *this.as_mut_ptr()
-        }
+    /// # Safety
+    /// See [`SQE`]
+    unsafe fn prep_close(&mut self, fd: RawFd) -> &mut SQE {
+        do_prep(self, |sqe| sys::io_uring_prep_close(sqe, fd))
     }
 
-    pub fn set_flags(&mut self, flags: SubmissionFlags) {
-        self.sqe.flags = flags.bits()
+    /// Prepares a request to close the registered file `slot`.
+    ///
+    /// # Safety
+    /// See [`SQE`]
+    unsafe fn prep_close_direct(&mut self, slot: FixedFd) -> &mut SQE {
+        do_prep(self, |sqe| {
+            sys::io_uring_prep_close(sqe, 0);
+            set_file_index(sqe, slot);
+        })
     }
 
-    pub fn enable_flags(&mut self, flags: SubmissionFlags) {
-        self.sqe.flags |= flags.bits()
+    /// # Safety
+    /// See [`SQE`]
+    unsafe fn prep_statx(
+        &mut self,
+        dirfd: RawFd,
+        path: &CStr,
+        flags: StatxFlags,
+        mask: StatxMask,
+        statx: *mut Statx,
+    ) -> &mut SQE {
+        do_prep(self, |sqe| {
+            let statx: *mut libc::statx = statx.cast();
+            sys::io_uring_prep_statx(sqe, dirfd, path.as_ptr(), flags.bits(), mask.bits(), statx)
+        })
     }
 
-    pub fn set_user_data(&mut self, user_data: u64) {
-        self.sqe.user_data = user_data;
+    /// # Safety
+    /// See [`SQE`]
+    unsafe fn prep_renameat(
+        &mut self,
+        old_dirfd: RawFd,
+        old_path: &CStr,
+        new_dirfd: RawFd,
+        new_path: &CStr,
+        flags: RenameFlags,
+    ) -> &mut SQE {
+        do_prep(self, |sqe| {
+            let addr = old_path.as_ptr().cast();
+            let addr2 = new_path.as_ptr() as u64;
+            prep_rw(
+                sqe,
+                Opcode::Renameat,
+                old_dirfd,
+                addr,
+                new_dirfd as u32,
+                addr2,
+            );
+            set_op_flags(sqe, flags.bits());
+        })
     }
-}
 
-bitflags! {
-    pub struct SubmissionFlags: u8 {
-        const FIXED_FILE    = sys::IOSQE_FIXED_FILE;
-        const IO_DRAIN      = sys::IOSQE_IO_DRAIN;
-        const IO_LINK       = sys::IOSQE_IO_LINK;
-        const IO_HARDLINK   = sys::IOSQE_IO_HARDLINK;
-        const ASYNC         = sys::IOSQE_ASYNC;
-        const BUFFER_SELECT = sys::IOSQE_BUFFER_SELECT;
+    /// # Safety
+    /// See [`SQE`]
+    unsafe fn prep_unlinkat(&mut self, dirfd: RawFd, path: &CStr, flags: UnlinkFlags) -> &mut SQE {
+        do_prep(self, |sqe| {
+            let addr = path.as_ptr().cast();
+            prep_rw(sqe, Opcode::Unlinkat, dirfd, addr, 0, 0);
+            set_op_flags(sqe, flags.bits() as u32);
+        })
     }
-}
 
-bitflags! {
-    pub struct FsyncFlags: u32 {
-        const FSYNC_DATASYNC    = sys::IORING_FSYNC_DATASYNC;
+    /// # Safety
+    /// See [`SQE`]
+    unsafe fn prep_mkdirat(&mut self, dirfd: RawFd, path: &CStr, mode: u32) -> &mut SQE {
+        do_prep(self, |sqe| {
+            let addr = path.as_ptr().cast();
+            prep_rw(sqe, Opcode::Mkdirat, dirfd, addr, mode, 0);
+        })
     }
-}
 
-impl PrepareSqe for SQE {
-    fn as_raw_mut_sqe(&mut self) -> *mut SQE {
-        self
+    /// Prepares a request to create a symbolic link at `link_path` which contains `target`.
+    ///
+    /// # Safety
+    /// See [`SQE`]
+    unsafe fn prep_symlinkat(
+        &mut self,
+        target: &CStr,
+        new_dirfd: RawFd,
+        link_path: &CStr,
+    ) -> &mut SQE {
+        do_prep(self, |sqe| {
+            let addr = target.as_ptr().cast();
+            let addr2 = link_path.as_ptr() as u64;
+            prep_rw(sqe, Opcode::Symlinkat, new_dirfd, addr, 0, addr2);
+        })
     }
-}
 
-impl PrepareSqe for MaybeUninit<SQE> {
-    fn as_raw_mut_sqe(&mut self) -> *mut SQE {
-        self.as_mut_ptr()
+    /// # Safety
+    /// See [`SQE`]
+    unsafe fn prep_linkat(
+        &mut self,
+        old_dirfd: RawFd,
+        old_path: &CStr,
+        new_dirfd: RawFd,
+        new_path: &CStr,
+        flags: LinkFlags,
+    ) -> &mut SQE {
+        do_prep(self, |sqe| {
+            let addr = old_path.as_ptr().cast();
+            let addr2 = new_path.as_ptr() as u64;
+            prep_rw(
+                sqe,
+                Opcode::Linkat,
+                old_dirfd,
+                addr,
+                new_dirfd as u32,
+                addr2,
+            );
+            set_op_flags(sqe, flags.bits() as u32);
+        })
     }
-}
 
-unsafe fn do_prep(
-    this: &mut (impl PrepareSqe + ?Sized),
-    f: impl FnOnce(*mut sys::io_uring_sqe),
-) -> &mut SQE {
-    let sqe = this.as_raw_mut_sqe();
-    f(sqe.cast());
-    &mut *sqe
-}
+    /// Prepares a request to read the extended attribute `name` of `fd` into `value`.
+    ///
+    /// The CQE result is the size of the value.
+    ///
+    /// # Safety
+    /// See [`SQE`]
+    unsafe fn prep_fgetxattr(
+        &mut self,
+        fd: impl Into<Target>,
+        name: &CStr,
+        value: *mut u8,
+        len: u32,
+    ) -> &mut SQE {
+        let fd = fd.into();
+        do_prep(self, |sqe| {
+            let (op, flags) = (Opcode::Fgetxattr, XattrFlags::empty());
+            prep_xattr(sqe, op, fd.raw(), ptr::null(), name, value, len, flags);
+            set_target(sqe, fd);
+        })
+    }
 
-pub trait PrepareSqe {
-    fn as_raw_mut_sqe(&mut self) -> *mut SQE;
+    /// Prepares a request to set the extended attribute `name` of `fd` to `value`.
+    ///
+    /// # Safety
+    /// See [`SQE`]
+    unsafe fn prep_fsetxattr(
+        &mut self,
+        fd: impl Into<Target>,
+        name: &CStr,
+        value: *const u8,
+        len: u32,
+        flags: XattrFlags,
+    ) -> &mut SQE {
+        let fd = fd.into();
+        do_prep(self, |sqe| {
+            let op = Opcode::Fsetxattr;
+            prep_xattr(sqe, op, fd.raw(), ptr::null(), name, value, len, flags);
+            set_target(sqe, fd);
+        })
+    }
 
+    /// Prepares a request to read the extended attribute `name` of `path` into `value`.
+    ///
+    /// The CQE result is the size of the value.
+    ///
     /// # Safety
     /// See [`SQE`]
-    unsafe fn prep_nop(&mut self) -> &mut SQE {
-        do_prep(self, |sqe| sys::io_uring_prep_nop(sqe))
+    unsafe fn prep_getxattr(
+        &mut self,
+        path: &CStr,
+        name: &CStr,
+        value: *mut u8,
+        len: u32,
+    ) -> &mut SQE {
+        do_prep(self, |sqe| {
+            let (op, flags) = (Opcode::Getxattr, XattrFlags::empty());
+            prep_xattr(sqe, op, 0, path.as_ptr(), name, value, len, flags);
+        })
     }
 
+    /// Prepares a request to set the extended attribute `name` of `path` to `value`.
+    ///
     /// # Safety
     /// See [`SQE`]
-    unsafe fn prep_read(
+    unsafe fn prep_setxattr(
         &mut self,
-        fd: RawFd,
-        buf: *mut u8,
-        n_bytes: usize,
-        offset: isize,
+        path: &CStr,
+        name: &CStr,
+        value: *const u8,
+        len: u32,
+        flags: XattrFlags,
     ) -> &mut SQE {
         do_prep(self, |sqe| {
-            sys::io_uring_prep_read(sqe, fd, buf.cast(), n_bytes as u32, offset as libc::off_t)
+            let op = Opcode::Setxattr;
+            prep_xattr(sqe, op, 0, path.as_ptr(), name, value, len, flags);
         })
     }
 
     /// # Safety
     /// See [`SQE`]
-    unsafe fn prep_readv(
+    unsafe fn prep_sync_file_range(
         &mut self,
-        fd: RawFd,
-        iovecs: *const libc::iovec,
-        n_vecs: usize,
-        offset: isize,
+        fd: impl Into<Target>,
+        offset: u64,
+        n_bytes: u32,
+        flags: SyncFileRangeFlags,
     ) -> &mut SQE {
+        let fd = fd.into();
         do_prep(self, |sqe| {
-            sys::io_uring_prep_readv(sqe, fd, iovecs.cast(), n_vecs as u32, offset as libc::off_t)
+            let op = Opcode::SyncFileRange;
+            prep_rw(sqe, op, fd.raw(), ptr::null(), n_bytes, offset);
+            set_target(sqe, fd);
+            set_op_flags(sqe, flags.bits());
         })
     }
 
     /// # Safety
     /// See [`SQE`]
-    unsafe fn prep_write(
+    unsafe fn prep_fallocate(
         &mut self,
-        fd: RawFd,
-        buf: *const u8,
-        n_bytes: usize,
-        offset: isize,
+        fd: impl Into<Target>,
+        mode: FallocateFlags,
+        offset: u64,
+        len: u64,
     ) -> &mut SQE {
+        let fd = fd.into();
         do_prep(self, |sqe| {
-            sys::io_uring_prep_write(sqe, fd, buf.cast(), n_bytes as u32, offset as libc::off_t)
+            let (offset, len) = (offset as libc::off_t, len as libc::off_t);
+            sys::io_uring_prep_fallocate(sqe, fd.raw(), mode.bits(), offset, len);
+            set_target(sqe, fd);
         })
     }
 
     /// # Safety
     /// See [`SQE`]
-    unsafe fn prep_writev(
+    unsafe fn prep_fadvise(
         &mut self,
-        fd: RawFd,
-        iovecs: *const libc::iovec,
-        n_vecs: usize,
-        offset: isize,
+        fd: impl Into<Target>,
+        offset: u64,
+        len: u32,
+        advice: FileAdvice,
     ) -> &mut SQE {
+        let fd = fd.into();
+        do_prep(self, |sqe| {
+            prep_rw(sqe, Opcode::Fadvise, fd.raw(), ptr::null(), len, offset);
+            set_target(sqe, fd);
+            set_op_flags(sqe, advice as u32);
+        })
+    }
+
+    /// # Safety
+    /// See [`SQE`]
+    unsafe fn prep_madvise(&mut self, addr: *mut u8, len: u32, advice: MemoryAdvice) -> &mut SQE {
         do_prep(self, |sqe| {
-            sys::io_uring_prep_writev(sqe, fd, iovecs, n_vecs as u32, offset as libc::off_t)
+            prep_rw(sqe, Opcode::Madvise, -1, addr.cast(), len, 0);
+            set_op_flags(sqe, advice as u32);
         })
     }
 
     /// # Safety
     /// See [`SQE`]
-    unsafe fn prep_fsync(&mut self, fd: RawFd, flags: FsyncFlags) -> &mut SQE {
-        do_prep(self, |sqe| sys::io_uring_prep_fsync(sqe, fd, flags.bits()))
+    unsafe fn prep_ftruncate(&mut self, fd: impl Into<Target>, len: u64) -> &mut SQE {
+        let fd = fd.into();
+        do_prep(self, |sqe| {
+            prep_rw(sqe, Opcode::Ftruncate, fd.raw(), ptr::null(), 0, len);
+            set_target(sqe, fd);
+        })
     }
 
+    /// Prepares a request to move `n_bytes` from `fd_in` to `fd_out`.
+    ///
+    /// One of the fds must be a pipe. The offset of a pipe must be `None`.
+    /// The offset of a file is the current position if it is `None`.
+    ///
     /// # Safety
     /// See [`SQE`]
-    unsafe fn prep_read_fixed(
+    unsafe fn prep_splice(
         &mut self,
-        fd: RawFd,
-        buf: *mut u8,
-        n_bytes: usize,
-        offset: isize,
-        buf_index: usize,
+        fd_in: impl Into<Target>,
+        off_in: Option<u64>,
+        fd_out: impl Into<Target>,
+        off_out: Option<u64>,
+        n_bytes: u32,
+        flags: SpliceFlags,
     ) -> &mut SQE {
+        // `sys::io_uring_prep_splice` passes its arguments in the wrong order
+        let (fd_in, fd_out) = (fd_in.into(), fd_out.into());
+        let mut flags = flags;
+        if fd_in.is_fixed() {
+            flags |= SpliceFlags::FD_IN_FIXED;
+        }
         do_prep(self, |sqe| {
-            sys::io_uring_prep_read_fixed(
+            let off_in = off_in.unwrap_or(u64::MAX);
+            let off_out = off_out.unwrap_or(u64::MAX);
+            prep_rw(
                 sqe,
-                fd,
-                buf.cast(),
-                n_bytes as u32,
-                offset as libc::off_t,
-                buf_index as i32,
-            )
+                Opcode::Splice,
+                fd_out.raw(),
+                ptr::null(),
+                n_bytes,
+                off_out,
+            );
+            (*sqe).addr = off_in;
+            (*sqe).buf_index.buf_index.splice_fd_in = fd_in.raw();
+            set_op_flags(sqe, flags.bits());
+            set_target(sqe, fd_out);
         })
     }
 
+    /// Prepares a request to duplicate `n_bytes` from the pipe `fd_in` to the pipe `fd_out`.
+    ///
     /// # Safety
     /// See [`SQE`]
-    unsafe fn prep_write_fixed(
+    unsafe fn prep_tee(
         &mut self,
-        fd: RawFd,
-        buf: *const u8,
-        n_bytes: usize,
-        offset: isize,
-        buf_index: usize,
+        fd_in: impl Into<Target>,
+        fd_out: impl Into<Target>,
+        n_bytes: u32,
+        flags: SpliceFlags,
     ) -> &mut SQE {
+        let (fd_in, fd_out) = (fd_in.into(), fd_out.into());
+        let mut flags = flags;
+        if fd_in.is_fixed() {
+            flags |= SpliceFlags::FD_IN_FIXED;
+        }
         do_prep(self, |sqe| {
-            sys::io_uring_prep_write_fixed(
+            prep_rw(sqe, Opcode::Tee, fd_out.raw(), ptr::null(), n_bytes, 0);
+            (*sqe).buf_index.buf_index.splice_fd_in = fd_in.raw();
+            set_op_flags(sqe, flags.bits());
+            set_target(sqe, fd_out);
+        })
+    }
+
+    /// Prepares a request to provide `n_bufs` buffers of `buf_len` bytes to the group `buf_group`.
+    ///
+    /// The buffers are contiguous from `addr`. Their ids start from `start_bid`.
+    ///
+    /// # Safety
+    /// See [`SQE`]
+    unsafe fn prep_provide_buffers(
+        &mut self,
+        addr: *mut u8,
+        buf_len: u32,
+        n_bufs: u16,
+        buf_group: u16,
+        start_bid: u16,
+    ) -> &mut SQE {
+        do_prep(self, |sqe| {
+            sys::io_uring_prep_provide_buffers(
                 sqe,
-                fd,
-                buf.cast(),
-                n_bytes as u32,
-                offset as libc::off_t,
-                buf_index as i32,
+                addr.cast(),
+                buf_len as i32,
+                i32::from(n_bufs),
+                i32::from(buf_group),
+                i32::from(start_bid),
             )
         })
     }
 
+    /// Prepares a request to remove at most `n_bufs` buffers from the group `buf_group`.
+    ///
+    /// # Safety
+    /// See [`SQE`]
+    unsafe fn prep_remove_buffers(&mut self, n_bufs: u16, buf_group: u16) -> &mut SQE {
+        do_prep(self, |sqe| {
+            sys::io_uring_prep_remove_buffers(sqe, i32::from(n_bufs), i32::from(buf_group))
+        })
+    }
+
+    /// Prepares a request to wait on the 32-bit futex at `futex` while it holds `val`.
+    ///
+    /// Only wakers whose mask intersects `mask` wake the request.
+    /// Pass `u32::MAX` to match any waker.
+    ///
+    /// The CQE result is `-EAGAIN` if the futex does not hold `val`.
+    ///
+    /// # Safety
+    /// See [`SQE`]
+    unsafe fn prep_futex_wait(
+        &mut self,
+        futex: *const u32,
+        val: u32,
+        mask: u32,
+        flags: FutexFlags,
+    ) -> &mut SQE {
+        do_prep(self, |sqe| {
+            let futex_flags = (sys::FUTEX2_SIZE_U32 | flags.bits()) as RawFd;
+            let val = u64::from(val);
+            prep_rw(sqe, Opcode::FutexWait, futex_flags, futex.cast(), 0, val);
+            (*sqe).buf_index.__pad2[1] = u64::from(mask);
+        })
+    }
+
+    /// Prepares a request to wake at most `n_wake` waiters of the 32-bit futex at `futex`.
+    ///
+    /// Only waiters whose mask intersects `mask` are woken.
+    /// Pass `u32::MAX` to match any waiter.
+    ///
+    /// The CQE result is the number of woken waiters.
+    ///
+    /// # Safety
+    /// See [`SQE`]
+    unsafe fn prep_futex_wake(
+        &mut self,
+        futex: *const u32,
+        n_wake: u32,
+        mask: u32,
+        flags: FutexFlags,
+    ) -> &mut SQE {
+        do_prep(self, |sqe| {
+            let futex_flags = (sys::FUTEX2_SIZE_U32 | flags.bits()) as RawFd;
+            let n_wake = u64::from(n_wake);
+            prep_rw(sqe, Opcode::FutexWake, futex_flags, futex.cast(), 0, n_wake);
+            (*sqe).buf_index.__pad2[1] = u64::from(mask);
+        })
+    }
+
+    /// Prepares a request to wait on any of the `n_futexes` futexes at `futexv`.
+    ///
+    /// The CQE result is the index of the woken futex.
+    ///
+    /// # Safety
+    /// See [`SQE`]
+    unsafe fn prep_futex_waitv(&mut self, futexv: *const FutexWaitv, n_futexes: u32) -> &mut SQE {
+        do_prep(self, |sqe| {
+            prep_rw(sqe, Opcode::FutexWaitv, 0, futexv.cast(), n_futexes, 0);
+        })
+    }
+
+    /// Prepares a `waitid` request which waits for a state change of child processes.
+    ///
+    /// # Safety
+    /// See [`SQE`]
+    unsafe fn prep_waitid(
+        &mut self,
+        idtype: libc::idtype_t,
+        id: libc::id_t,
+        infop: *mut libc::siginfo_t,
+        options: WaitFlags,
+    ) -> &mut SQE {
+        do_prep(self, |sqe| {
+            let infop = infop as u64;
+            prep_rw(sqe, Opcode::Waitid, id as RawFd, ptr::null(), idtype, infop);
+            (*sqe).buf_index.buf_index.splice_fd_in = options.bits();
+        })
+    }
+
+    /// Prepares a request to post a CQE with `result` and `user_data` to the ring `ring_fd`.
+    ///
+    /// See [`RingHandle`](crate::ring::RingHandle) for a target which can be sent to other threads.
+    ///
+    /// # Safety
+    /// See [`SQE`]
+    unsafe fn prep_msg_ring(
+        &mut self,
+        ring_fd: impl Into<Target>,
+        result: i32,
+        user_data: u64,
+    ) -> &mut SQE {
+        let ring_fd = ring_fd.into();
+        do_prep(self, |sqe| {
+            let (cmd, len) = (sys::IORING_MSG_DATA, result as u32);
+            prep_rw(
+                sqe,
+                Opcode::MsgRing,
+                ring_fd.raw(),
+                ptr::null(),
+                len,
+                user_data,
+            );
+            (*sqe).addr = cmd;
+            set_target(sqe, ring_fd);
+        })
+    }
+
+    /// Prepares a request to install the registered file `src` of this ring
+    /// into the registered file slot `dst` of the ring `ring_fd`.
+    ///
+    /// The target ring receives a CQE with `user_data`.
+    /// `src` keeps its file. Close it by [`PrepareSqe::prep_close_direct`] to complete a move.
+    ///
+    /// # Safety
+    /// See [`SQE`]
+    unsafe fn prep_msg_ring_fd(
+        &mut self,
+        ring_fd: impl Into<Target>,
+        src: FixedFd,
+        dst: FixedFd,
+        user_data: u64,
+    ) -> &mut SQE {
+        let ring_fd = ring_fd.into();
+        do_prep(self, |sqe| {
+            let cmd = sys::IORING_MSG_SEND_FD;
+            prep_rw(
+                sqe,
+                Opcode::MsgRing,
+                ring_fd.raw(),
+                ptr::null(),
+                0,
+                user_data,
+            );
+            (*sqe).addr = cmd;
+            (*sqe).buf_index.__pad2[1] = u64::from(src.index());
+            set_file_index(sqe, dst);
+            set_target(sqe, ring_fd);
+        })
+    }
+
     // TODO: impl more prep_* methods
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn opcode_from_u8() {
+        for (i, &op) in Opcode::ALL.iter().enumerate() {
+            assert_eq!(Opcode::from_u8(i as u8), Some(op));
+            assert_eq!(Opcode::from_u8(op as u8), Some(op));
+        }
+        assert_eq!(Opcode::from_u8(Opcode::Pipe as u8), Some(Opcode::Pipe));
+        assert_eq!(Opcode::from_u8(Opcode::Pipe as u8 + 1), None);
+        assert_eq!(Opcode::from_u8(u8::MAX), None);
+    }
+
+    #[test]
+    fn set_flags_fixed_file() {
+        let mut sqe = SQE::new_uninit();
+
+        let sqe = unsafe { sqe.prep_fsync(Target::Fixed(3), FsyncFlags::empty()) };
+        sqe.set_flags(SubmissionFlags::IO_LINK);
+        assert_eq!(
+            sqe.flags(),
+            SubmissionFlags::IO_LINK | SubmissionFlags::FIXED_FILE
+        );
+
+        let sqe = unsafe { sqe.prep_fsync(Target::Fd(3), FsyncFlags::empty()) };
+        sqe.set_flags(SubmissionFlags::ASYNC);
+        assert_eq!(sqe.flags(), SubmissionFlags::ASYNC);
+
+        let flags = SubmissionFlags::FIXED_FILE | SubmissionFlags::ASYNC;
+        sqe.set_flags(flags);
+        assert_eq!(sqe.flags(), flags);
+
+        let sqe = unsafe { sqe.prep_fsync(3, FsyncFlags::empty()) };
+        sqe.enable_flags(SubmissionFlags::FIXED_FILE);
+        assert_eq!(sqe.flags(), SubmissionFlags::FIXED_FILE);
+    }
+
+    #[test]
+    fn timespec_from_duration() {
+        let ts = Timespec::from(Duration::new(3, 250_000_000));
+        assert_eq!((ts.sec(), ts.nsec()), (3, 250_000_000));
+
+        let ts = Timespec::from(Duration::from_nanos(999_999_999));
+        assert_eq!((ts.sec(), ts.nsec()), (0, 999_999_999));
+
+        let ts = Timespec::from(Duration::new(u64::MAX, 1));
+        assert_eq!((ts.sec(), ts.nsec()), (i64::MAX, 1));
+    }
+
+    #[test]
+    #[should_panic]
+    fn timespec_rejects_overflowing_nsec() {
+        let _ = Timespec::new(0, 1_000_000_000);
+    }
+
+    #[test]
+    fn io_priority_encoding() {
+        let prio = IoPriority::new(IoPriorityClass::BestEffort, 4);
+        assert_eq!(prio.raw(), (2 << 13) | 4);
+        assert_eq!(prio.class(), Some(IoPriorityClass::BestEffort));
+        assert_eq!(prio.level(), 4);
+
+        for class in [
+            IoPriorityClass::None,
+            IoPriorityClass::RealTime,
+            IoPriorityClass::BestEffort,
+            IoPriorityClass::Idle,
+        ] {
+            let prio = IoPriority::from_raw(IoPriority::new(class, 7).raw());
+            assert_eq!(prio.class(), Some(class));
+            assert_eq!(prio.level(), 7);
+        }
+
+        assert_eq!(IoPriority::from_raw(4 << 13).class(), None);
+    }
+
+    #[test]
+    #[should_panic]
+    fn io_priority_rejects_level_8() {
+        IoPriority::new(IoPriorityClass::Idle, 8);
+    }
+}
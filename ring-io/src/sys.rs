@@ -1 +1,126 @@
 pub use uring_sys::*;
+
+// io_uring_setup flags (newer than uring-sys)
+pub const IORING_SETUP_SUBMIT_ALL: libc::c_uint = 1 << 7;
+pub const IORING_SETUP_COOP_TASKRUN: libc::c_uint = 1 << 8;
+pub const IORING_SETUP_TASKRUN_FLAG: libc::c_uint = 1 << 9;
+pub const IORING_SETUP_SINGLE_ISSUER: libc::c_uint = 1 << 12;
+pub const IORING_SETUP_DEFER_TASKRUN: libc::c_uint = 1 << 13;
+
+// io_uring_params.features (newer than uring-sys)
+pub const IORING_FEAT_SQPOLL_NONFIXED: libc::__u32 = 1 << 7;
+pub const IORING_FEAT_EXT_ARG: libc::__u32 = 1 << 8;
+pub const IORING_FEAT_NATIVE_WORKERS: libc::__u32 = 1 << 9;
+pub const IORING_FEAT_RSRC_TAGS: libc::__u32 = 1 << 10;
+pub const IORING_FEAT_CQE_SKIP: libc::__u32 = 1 << 11;
+pub const IORING_FEAT_LINKED_FILE: libc::__u32 = 1 << 12;
+pub const IORING_FEAT_REG_REG_RING: libc::__u32 = 1 << 13;
+pub const IORING_FEAT_RECVSEND_BUNDLE: libc::__u32 = 1 << 14;
+pub const IORING_FEAT_MIN_TIMEOUT: libc::__u32 = 1 << 15;
+
+// sqe.opcode (newer than uring-sys)
+pub const IORING_OP_SHUTDOWN: u8 = 34;
+pub const IORING_OP_RENAMEAT: u8 = 35;
+pub const IORING_OP_UNLINKAT: u8 = 36;
+pub const IORING_OP_MKDIRAT: u8 = 37;
+pub const IORING_OP_SYMLINKAT: u8 = 38;
+pub const IORING_OP_LINKAT: u8 = 39;
+pub const IORING_OP_MSG_RING: u8 = 40;
+pub const IORING_OP_FSETXATTR: u8 = 41;
+pub const IORING_OP_SETXATTR: u8 = 42;
+pub const IORING_OP_FGETXATTR: u8 = 43;
+pub const IORING_OP_GETXATTR: u8 = 44;
+pub const IORING_OP_SOCKET: u8 = 45;
+pub const IORING_OP_URING_CMD: u8 = 46;
+pub const IORING_OP_SEND_ZC: u8 = 47;
+pub const IORING_OP_SENDMSG_ZC: u8 = 48;
+pub const IORING_OP_READ_MULTISHOT: u8 = 49;
+pub const IORING_OP_WAITID: u8 = 50;
+pub const IORING_OP_FUTEX_WAIT: u8 = 51;
+pub const IORING_OP_FUTEX_WAKE: u8 = 52;
+pub const IORING_OP_FUTEX_WAITV: u8 = 53;
+pub const IORING_OP_FIXED_FD_INSTALL: u8 = 54;
+pub const IORING_OP_FTRUNCATE: u8 = 55;
+pub const IORING_OP_BIND: u8 = 56;
+pub const IORING_OP_LISTEN: u8 = 57;
+pub const IORING_OP_RECV_ZC: u8 = 58;
+pub const IORING_OP_EPOLL_WAIT: u8 = 59;
+pub const IORING_OP_READV_FIXED: u8 = 60;
+pub const IORING_OP_WRITEV_FIXED: u8 = 61;
+pub const IORING_OP_PIPE: u8 = 62;
+
+// sqe.len for IORING_OP_POLL_ADD and IORING_OP_POLL_REMOVE
+pub const IORING_POLL_ADD_MULTI: libc::__u32 = 1 << 0;
+pub const IORING_POLL_UPDATE_EVENTS: libc::__u32 = 1 << 1;
+pub const IORING_POLL_UPDATE_USER_DATA: libc::__u32 = 1 << 2;
+
+pub const IORING_RECV_MULTISHOT: libc::__u16 = 1 << 1;
+pub const IORING_ACCEPT_MULTISHOT: libc::__u16 = 1 << 0;
+
+pub const IORING_MSG_DATA: libc::__u64 = 0;
+pub const IORING_MSG_SEND_FD: libc::__u64 = 1;
+
+// cqe.flags
+pub const IORING_CQE_BUFFER_SHIFT: libc::c_uint = 16; // uring-sys defines a wrong value
+pub const IORING_CQE_F_BUFFER: libc::__u32 = 1 << 0;
+pub const IORING_CQE_F_MORE: libc::__u32 = 1 << 1;
+pub const IORING_CQE_F_SOCK_NONEMPTY: libc::__u32 = 1 << 2;
+pub const IORING_CQE_F_NOTIF: libc::__u32 = 1 << 3;
+
+// sqe.cmd_flags.timeout_flags (newer than uring-sys)
+pub const IORING_TIMEOUT_UPDATE: libc::__u32 = 1 << 1;
+pub const IORING_TIMEOUT_BOOTTIME: libc::__u32 = 1 << 2;
+pub const IORING_TIMEOUT_REALTIME: libc::__u32 = 1 << 3;
+pub const IORING_LINK_TIMEOUT_UPDATE: libc::__u32 = 1 << 4;
+pub const IORING_TIMEOUT_ETIME_SUCCESS: libc::__u32 = 1 << 5;
+
+// sqe.cmd_flags.cancel_flags
+pub const IORING_ASYNC_CANCEL_ALL: libc::__u32 = 1 << 0;
+pub const IORING_ASYNC_CANCEL_FD: libc::__u32 = 1 << 1;
+pub const IORING_ASYNC_CANCEL_ANY: libc::__u32 = 1 << 2;
+pub const IORING_ASYNC_CANCEL_FD_FIXED: libc::__u32 = 1 << 3;
+
+// io_uring_register opcodes (newer than uring-sys)
+pub const IORING_REGISTER_PBUF_RING: libc::c_uint = 22;
+pub const IORING_UNREGISTER_PBUF_RING: libc::c_uint = 23;
+pub const IORING_REGISTER_SYNC_CANCEL: libc::c_uint = 24;
+
+#[repr(C)]
+pub struct io_uring_sync_cancel_reg {
+    pub addr: libc::__u64,
+    pub fd: libc::__s32,
+    pub flags: libc::__u32,
+    pub timeout: __kernel_timespec,
+    pub opcode: libc::__u8,
+    pub pad: [libc::__u8; 7],
+    pub pad2: [libc::__u64; 3],
+}
+
+#[repr(C)]
+pub struct io_uring_buf {
+    pub addr: libc::__u64,
+    pub len: libc::__u32,
+    pub bid: libc::__u16,
+    pub resv: libc::__u16,
+}
+
+#[repr(C)]
+pub struct io_uring_buf_reg {
+    pub ring_addr: libc::__u64,
+    pub ring_entries: libc::__u32,
+    pub bgid: libc::__u16,
+    pub flags: libc::__u16,
+    pub resv: [libc::__u64; 3],
+}
+
+pub const FUTEX2_SIZE_U32: libc::__u32 = 0x02;
+pub const FUTEX2_PRIVATE: libc::__u32 = 128;
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct futex_waitv {
+    pub val: libc::__u64,
+    pub uaddr: libc::__u64,
+    pub flags: libc::__u32,
+    pub __reserved: libc::__u32,
+}
@@ -1,4 +1,5 @@
 use std::io;
+use std::ptr::{self, NonNull};
 
 pub fn resultify(x: i32) -> io::Result<u32> {
     if x >= 0 {
@@ -7,3 +8,29 @@ pub fn resultify(x: i32) -> io::Result<u32> {
         Err(io::Error::from_raw_os_error(-x))
     }
 }
+
+/// An anonymous page-aligned memory mapping.
+pub struct Mmap {
+    pub ptr: NonNull<u8>,
+    pub len: usize,
+}
+
+impl Mmap {
+    pub fn new(len: usize) -> io::Result<Self> {
+        let len = len.max(1);
+        let prot = libc::PROT_READ | libc::PROT_WRITE;
+        let flags = libc::MAP_PRIVATE | libc::MAP_ANONYMOUS | libc::MAP_POPULATE;
+        let ptr = unsafe { libc::mmap(ptr::null_mut(), len, prot, flags, -1, 0) };
+        if ptr == libc::MAP_FAILED {
+            return Err(io::Error::last_os_error());
+        }
+        let ptr = unsafe { NonNull::new_unchecked(ptr.cast()) };
+        Ok(Self { ptr, len })
+    }
+}
+
+impl Drop for Mmap {
+    fn drop(&mut self) {
+        unsafe { libc::munmap(self.ptr.as_ptr().cast(), self.len) };
+    }
+}